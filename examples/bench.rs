@@ -0,0 +1,62 @@
+//! Measures the cost of a log call on this machine.
+//!
+//! Run in release mode, optionally with the number of iterations:
+//!
+//! ```text
+//! cargo run --release --example bench -- 1000000
+//! cargo run --release --example bench --features blocking
+//! ```
+
+use std::{hint::black_box, time::Instant};
+use traccia::{Config, LogLevel, MemoryTarget, NullLogger, debug, info, log_to};
+
+fn measure<F: FnMut(usize)>(name: &str, iterations: usize, mut f: F) {
+    let start = Instant::now();
+
+    for i in 0..iterations {
+        f(black_box(i));
+    }
+
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<40} {:>10.1} ns/call",
+        name,
+        elapsed.as_nanos() as f64 / iterations as f64
+    );
+}
+
+fn main() {
+    let iterations = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(1_000_000);
+
+    traccia::init_with_config(
+        Config::default()
+            .with_level(LogLevel::Info)
+            .with_target(MemoryTarget::with_capacity(1024)),
+    );
+
+    println!("{} iterations\n", iterations);
+
+    measure("log_to! with a NullLogger", iterations, |i| {
+        log_to!(&NullLogger, LogLevel::Debug, "iteration {}", i)
+    });
+
+    measure("debug! below the level", iterations, |i| {
+        debug!("iteration {}", i)
+    });
+
+    measure("info! to a memory target", iterations, |i| {
+        info!("iteration {}", i)
+    });
+
+    let start = Instant::now();
+    traccia::flush();
+
+    println!(
+        "\nWaited {:?} for the queued records to be written",
+        start.elapsed()
+    );
+}
@@ -8,10 +8,10 @@ fn main() {
     })));
 
     traccia::set_hook(Hook::BeforeLog(Box::new(|level, target| {
-        if let TargetId::File(_) = target {
-            if level == LogLevel::Info {
-                println!("This will be printed only before calling the info! macro on a file.")
-            }
+        if let TargetId::File(_) = target
+            && level == LogLevel::Info
+        {
+            println!("This will be printed only before calling the info! macro on a file.")
         }
     })));
 
@@ -21,6 +21,16 @@ fn main() {
         }
     })));
 
+    traccia::set_hook(Hook::AfterLogRecord(Box::new(
+        |record, formatted, target| {
+            if let TargetId::File(_) = target
+                && record.level >= LogLevel::Warn
+            {
+                println!("Notification: {:?} was written to the file", formatted);
+            }
+        },
+    )));
+
     traccia::init_with_config(traccia::Config {
         level: LogLevel::Trace,
         targets: vec![
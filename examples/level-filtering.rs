@@ -1,4 +1,4 @@
-use traccia::{FileMode, LogLevel, error, fatal, info};
+use traccia::{FileMode, LogLevel, error, fatal, info, warn};
 
 fn main() {
     traccia::init_with_config(traccia::Config {
@@ -8,13 +8,19 @@ fn main() {
             Box::new(
                 traccia::File::new("./.logs/latest.log", FileMode::Truncate)
                     .expect("Failed to open file.")
-                    .filtered(LogLevel::Fatal),
+                    .min_level(LogLevel::Error),
+            ),
+            Box::new(
+                traccia::File::new("./.logs/warnings.log", FileMode::Truncate)
+                    .expect("Failed to open file.")
+                    .only_level(LogLevel::Warn),
             ),
         ],
         ..Default::default()
     });
 
-    info!("This will not be written to latest.log, but will be printed to console.");
-    error!("It will write fatal messages only!");
+    info!("This will not be written to the files, but will be printed to console.");
+    warn!("This will be written to warnings.log only.");
+    error!("latest.log gets errors and fatal errors,");
     fatal!("Like this :(");
 }
@@ -13,6 +13,7 @@ fn main() {
         level: LogLevel::Debug,
         targets: vec![Box::new(Console::new())],
         format: Some(Box::new(DefaultFormatter::with_span_after_level())),
+        ..Default::default()
     };
 
     init_with_config(config);
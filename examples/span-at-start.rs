@@ -11,6 +11,7 @@ fn main() {
         level: LogLevel::Info,
         targets: vec![Box::new(Console::new())],
         format: Some(Box::new(DefaultFormatter::with_span_at_start())),
+        ..Default::default()
     };
 
     init_with_config(config);
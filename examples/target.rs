@@ -6,9 +6,12 @@ fn main() {
         targets: vec![
             Box::new(traccia::Console::new()),
             Box::new(
-                traccia::File::new(".logs/latest.log", FileMode::Truncate)
-                    .unwrap()
-                    .filtered(LogLevel::Fatal),
+                traccia::File::new_filtered(
+                    ".logs/latest.log",
+                    FileMode::Truncate,
+                    LogLevel::Fatal,
+                )
+                .unwrap(),
             ),
         ],
         ..Default::default()
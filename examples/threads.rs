@@ -4,13 +4,10 @@ struct CustomFormatter;
 
 impl traccia::Formatter for CustomFormatter {
     fn format(&self, record: &Record) -> String {
-        let id_str = format!("{:?}", record.thread_id);
-        let id_str = id_str.replace("ThreadId(", "").replace(")", "");
-
         format!(
             "[{}] [thread:{}] {}",
             record.level.default_coloring(),
-            id_str,
+            traccia::format_thread_id(record.thread_id),
             record.message,
         )
     }
@@ -0,0 +1,20 @@
+use traccia::{LogLevel, info, span, timed};
+
+fn main() {
+    traccia::init(LogLevel::Debug);
+
+    let _t = timed!(LogLevel::Info, "main");
+
+    for id in 0..3 {
+        query(id);
+    }
+
+    info!("All queries done");
+}
+
+fn query(id: u64) {
+    let _span = span!("query", "id" => id);
+    let _t = timed!("db query");
+
+    std::thread::sleep(std::time::Duration::from_millis(5 * id));
+}
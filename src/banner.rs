@@ -0,0 +1,106 @@
+//! Summary of the effective configuration, logged at initialization
+//! when [`Config::log_config_on_init`] is set.
+
+use crate::{Config, LogLevel, Record, Target, human_duration, with_thread_level};
+
+/// Describes the configuration, one line for the logger and one per target.
+pub(crate) fn describe(config: &Config) -> Vec<String> {
+    let mut lines = vec![format!(
+        "Logger initialized: level={}, formatter={}, targets={}, flush_interval={}, backtraces={}, router={}, denied_modules=[{}]",
+        config.level,
+        if config.format.is_some() {
+            "custom"
+        } else {
+            "default"
+        },
+        config.targets.len(),
+        config
+            .flush_interval
+            .map_or("off".to_string(), human_duration),
+        config
+            .capture_backtrace_at
+            .map_or("off".to_string(), |level| format!(">={}", level)),
+        if config.router.is_some() {
+            "custom"
+        } else {
+            "none"
+        },
+        config.denied_modules.join(", "),
+    )];
+
+    for (index, target) in config.targets.iter().enumerate() {
+        lines.push(format!(
+            "Target {}: {}, levels={}, colors={}",
+            index,
+            target.id(),
+            levels(target.as_ref()),
+            if target.wants_color() { "on" } else { "off" },
+        ));
+    }
+
+    lines
+}
+
+/// Describes the levels a target accepts.
+fn levels(target: &dyn Target) -> String {
+    let range = match (target.filter_level(), target.max_level()) {
+        (None, None) => None,
+        (Some(min), None) => Some(format!(">={}", min)),
+        (None, Some(max)) => Some(format!("<={}", max)),
+        (Some(min), Some(max)) => Some(format!("{}..={}", min, max)),
+    };
+
+    let only = target.only_levels().map(|only| {
+        let only = only.iter().map(ToString::to_string).collect::<Vec<_>>();
+        format!("[{}]", only.join(", "))
+    });
+
+    match (range, only) {
+        (None, None) => "all".to_string(),
+        (Some(range), None) => range,
+        (None, Some(only)) => only,
+        (Some(range), Some(only)) => format!("{} {}", range, only),
+    }
+}
+
+/// Logs the description of the configuration through the global logger.
+///
+/// The lines are logged at `INFO`, even if the configured level is higher:
+/// the level of the thread is lowered while logging them, so that they are
+/// not filtered out by the logger, without raising them to an alerting level.
+pub(crate) fn log_config(config: &Config) {
+    with_thread_level(LogLevel::Info, || {
+        for line in describe(config) {
+            crate::__log(
+                Record::builder(LogLevel::Info, line)
+                    .target("traccia")
+                    .module_path(module_path!())
+                    .build(),
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Console, MemoryTarget};
+
+    #[test]
+    fn test_describe() {
+        let config = Config::default()
+            .with_level(LogLevel::Debug)
+            .with_target(MemoryTarget::new().min_level(LogLevel::Warn))
+            .with_target(Console::stderr().only_levels(&[LogLevel::Error, LogLevel::Fatal]))
+            .with_denied_module("mio");
+
+        assert_eq!(
+            describe(&config),
+            [
+                "Logger initialized: level=DEBUG, formatter=default, targets=2, flush_interval=1.00s, backtraces=off, router=none, denied_modules=[mio]",
+                "Target 0: custom:memory, levels=>=WARN, colors=on",
+                "Target 1: console:stderr, levels=[ERROR, FATAL], colors=on",
+            ]
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Sources of the wall-clock time rendered in timestamps.
+//!
+//! The formatters read the time from a [`Clock`], [`SystemClock`] by default.
+//! A different clock can be given to the formatter, e.g. a [`FixedClock`]
+//! to get reproducible output in tests, or a closure for simulated time.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use std::time::{Duration, UNIX_EPOCH};
+//! use traccia::{DefaultFormatter, FixedClock, Section};
+//!
+//! // Every line starts with `2024-04-04T12:30:05.000Z`
+//! let formatter = DefaultFormatter::new()
+//!     .with_layout(&[Section::Timestamp, Section::Level, Section::Message])
+//!     .with_clock(FixedClock::new(UNIX_EPOCH + Duration::from_secs(1_712_233_805)));
+//! ```
+
+use std::time::SystemTime;
+
+/// A source of the current wall-clock time.
+///
+/// Implemented for closures returning a `SystemTime`.
+pub trait Clock: Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> SystemTime + Send + Sync,
+{
+    fn now(&self) -> SystemTime {
+        self()
+    }
+}
+
+/// Clock reading the system time, used by default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Clock always returning the same time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(SystemTime);
+
+impl FixedClock {
+    /// Creates a clock stopped at the given time.
+    pub fn new(time: SystemTime) -> Self {
+        Self(time)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
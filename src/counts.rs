@@ -0,0 +1,87 @@
+//! Per-level counters of the records logged so far,
+//! and a counter of the records suppressed by the logger level.
+
+use crate::LogLevel;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// One counter per built-in level, indexed by severity.
+static COUNTS: [AtomicU64; 6] = [const { AtomicU64::new(0) }; 6];
+
+/// Records rejected by the logger level.
+static SUPPRESSED: AtomicU64 = AtomicU64::new(0);
+
+/// Counts a record accepted by the logger.
+pub(crate) fn increment(level: LogLevel) {
+    let index = (level.severity() as usize).min(COUNTS.len() - 1);
+    COUNTS[index].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns how many records have been logged for each level,
+/// indexed by severity: `[trace, debug, info, warn, error, fatal]`.
+///
+/// Each record is counted once when it passes the logger level,
+/// regardless of how many targets it is written to.
+/// Custom levels are counted with the built-in level of the same severity,
+/// severities above `Fatal` are counted as `Fatal`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let [trace, debug, info, warn, error, fatal] = traccia::counts();
+///
+/// println!("{} errors and {} warnings so far", error, warn);
+/// ```
+pub fn counts() -> [u64; 6] {
+    std::array::from_fn(|i| COUNTS[i].load(Ordering::Relaxed))
+}
+
+/// Counts a record rejected by the logger.
+pub(crate) fn suppress() {
+    SUPPRESSED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns how many records have been dropped so far
+/// because they were below the logger level.
+///
+/// Records filtered out by the level of a single target are not counted,
+/// since they may still be written to the other targets.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// traccia::init(traccia::LogLevel::Info);
+///
+/// traccia::debug!("Not written");
+///
+/// println!("{} records suppressed", traccia::suppressed_count());
+/// ```
+pub fn suppressed_count() -> u64 {
+    SUPPRESSED.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_levels_counted_by_severity() {
+        let before = counts();
+
+        increment(LogLevel::custom("NOTICE", 2));
+        increment(LogLevel::custom("PANIC", 9));
+
+        let after = counts();
+
+        assert!(after[2] > before[2]);
+        assert!(after[5] > before[5]);
+    }
+
+    #[test]
+    fn test_suppressed_count() {
+        let before = suppressed_count();
+
+        suppress();
+
+        assert!(suppressed_count() > before);
+    }
+}
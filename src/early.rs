@@ -0,0 +1,162 @@
+//! Handling of records emitted before the logger is initialized.
+//!
+//! By default these records are discarded silently.
+//! Two opt-in behaviors are available:
+//!
+//! - [`buffer_early_logs`] keeps up to a given number of records in memory
+//!   and replays them through the logger as soon as it is initialized.
+//! - [`warn_if_uninitialized`] prints a one-time notice to stderr
+//!   the first time a record is discarded.
+
+use crate::{Logger, Record};
+use std::{
+    collections::VecDeque,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+/// Bounded storage for the records emitted before initialization.
+struct EarlyBuffer {
+    capacity: usize,
+    records: VecDeque<Record>,
+    dropped: usize,
+}
+
+static BUFFER: Mutex<Option<EarlyBuffer>> = Mutex::new(None);
+static BUFFERING: AtomicBool = AtomicBool::new(false);
+static REPLAYED: AtomicBool = AtomicBool::new(false);
+static WARN: AtomicBool = AtomicBool::new(false);
+static WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Enables buffering of the records emitted before the logger is initialized.
+///
+/// Up to `capacity` records are kept in memory, further records are discarded.
+/// Once the logger is initialized, the buffered records are logged in order,
+/// still subject to the configured level.
+///
+/// Passing a capacity of `0` disables buffering and discards
+/// any record buffered so far.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{LogLevel, info};
+///
+/// traccia::buffer_early_logs(128);
+///
+/// info!("Parsing arguments"); // Buffered
+///
+/// traccia::init(LogLevel::Info); // "Parsing arguments" is logged here
+/// ```
+pub fn buffer_early_logs(capacity: usize) {
+    let Ok(mut buffer) = BUFFER.lock() else {
+        return;
+    };
+
+    *buffer = (capacity > 0).then(|| EarlyBuffer {
+        capacity,
+        records: VecDeque::with_capacity(capacity),
+        dropped: 0,
+    });
+
+    BUFFERING.store(capacity > 0, Ordering::Relaxed);
+}
+
+/// Enables a one-time stderr notice when a record is discarded
+/// because the logger isn't initialized yet.
+pub fn warn_if_uninitialized(enabled: bool) {
+    WARN.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` if the records emitted while no logger is set are buffered.
+///
+/// Otherwise the record is discarded without being built,
+/// and the one-time notice is printed if enabled.
+pub(crate) fn accepts() -> bool {
+    if BUFFERING.load(Ordering::Relaxed) {
+        return true;
+    }
+
+    warn_discarded();
+    false
+}
+
+/// Returns `true` once the buffered records have been replayed through the logger.
+pub(crate) fn replayed() -> bool {
+    REPLAYED.load(Ordering::Acquire)
+}
+
+/// Handles a record emitted before the buffered records are replayed.
+///
+/// Returns the record back if the logger is set, for the caller to log it:
+/// the replay is done by the time the lock is acquired, so the record
+/// is written after the buffered ones.
+pub(crate) fn handle(record: Record) -> Option<Record> {
+    let mut buffer = BUFFER.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if crate::LOGGER.get().is_some() {
+        return Some(record);
+    }
+
+    if let Some(buffer) = buffer.as_mut() {
+        if buffer.records.len() < buffer.capacity {
+            buffer.records.push_back(record);
+        } else {
+            buffer.dropped += 1;
+        }
+
+        return None;
+    }
+
+    drop(buffer);
+    warn_discarded();
+
+    None
+}
+
+/// Prints the one-time notice about a discarded record, if enabled.
+fn warn_discarded() {
+    if WARN.load(Ordering::Relaxed) && !WARNED.swap(true, Ordering::Relaxed) {
+        eprintln!(
+            "traccia: a log record was emitted before the logger was initialized and has been discarded. \
+             Initialize the logger earlier, or enable `traccia::buffer_early_logs`."
+        );
+    }
+}
+
+/// Sets the logger with `set`, then logs all the buffered records through it.
+///
+/// The buffer stays locked from before the logger is set until the replay is done,
+/// so the records logged meanwhile wait in [`handle`] and are written after the buffered ones.
+///
+/// Returns `None` if `set` fails, e.g. because a logger is already set.
+pub(crate) fn install<F>(set: F) -> Option<&'static dyn Logger>
+where
+    F: FnOnce() -> Option<&'static dyn Logger>,
+{
+    let mut buffer = BUFFER.lock().unwrap_or_else(PoisonError::into_inner);
+    let logger = set()?;
+
+    if let Some(buffer) = buffer.as_mut() {
+        for record in buffer.records.drain(..) {
+            if crate::accepts(logger, &record) {
+                logger.log_owned(record);
+            }
+        }
+
+        if buffer.dropped > 0 {
+            eprintln!(
+                "traccia: {} log records emitted before initialization were discarded, the early buffer was full.",
+                buffer.dropped
+            );
+
+            buffer.dropped = 0;
+        }
+    }
+
+    REPLAYED.store(true, Ordering::Release);
+
+    Some(logger)
+}
@@ -1,3 +1,4 @@
+use crate::TargetId;
 use std::{fmt::Display, io, sync::PoisonError};
 
 #[derive(Debug)]
@@ -13,6 +14,24 @@ pub enum Error {
     Poisoned,
     /// Failed to convert `LogLevel` to something else or vice-versa
     ParseLogLevel,
+    /// A formatter failed to format a record (see `Formatter::try_format`)
+    Format(String),
+    /// The configuration has no targets, so nothing would be logged
+    NoTargets,
+    /// A target failed to write a record, reported by the logger
+    TargetWrite {
+        /// Id of the failing target
+        id: TargetId,
+        /// The error returned by the target
+        source: Box<Error>,
+    },
+    /// A target failed its validation (see `Config::validate`)
+    InvalidTarget {
+        /// Id of the invalid target
+        id: TargetId,
+        /// The error returned by the target
+        source: Box<Error>,
+    },
 }
 
 impl From<io::Error> for Error {
@@ -35,8 +54,26 @@ impl Display for Error {
             Error::AlreadyInitialized => write!(f, "A logger has already been initialized"),
             Error::Poisoned => write!(f, "Mutex is poisoned"),
             Error::ParseLogLevel => write!(f, "Could not parse log level from string"),
+            Error::Format(reason) => write!(f, "Failed to format record: {}", reason),
+            Error::NoTargets => write!(
+                f,
+                "The configuration has no targets, use `init_null` to disable logging on purpose"
+            ),
+            // The source is left to `source()`, see `error_chain` to print both
+            Error::TargetWrite { id, .. } => write!(f, "Failed to write to target {}", id),
+            Error::InvalidTarget { id, .. } => write!(f, "Target {} is misconfigured", id),
         }
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::TargetWrite { source, .. } | Error::InvalidTarget { source, .. } => {
+                Some(source.as_ref())
+            }
+            _ => None,
+        }
+    }
+}
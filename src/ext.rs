@@ -0,0 +1,179 @@
+//! Extension traits and helpers to log values inline.
+
+use crate::{LogLevel, Record};
+use std::{error::Error, panic::Location};
+
+/// Renders an error followed by its chain of sources, from the outermost to the root cause,
+/// e.g. `failed to load config: caused by: permission denied`.
+///
+/// Errors that already print their source in their own message are common,
+/// so a source whose message ends the previous one is left out instead of repeated.
+///
+/// Used by the [`log_error_chain!`](crate::log_error_chain) macro.
+/// Boxed errors can be passed with `as_ref`.
+///
+/// # Examples
+///
+/// ```
+/// use std::io;
+///
+/// let err = io::Error::new(io::ErrorKind::Other, "disk unreachable");
+///
+/// assert_eq!(traccia::error_chain(&err), "disk unreachable");
+/// ```
+pub fn error_chain<E>(err: &E) -> String
+where
+    E: Error + ?Sized,
+{
+    let mut chain = err.to_string();
+    let mut previous = chain.clone();
+    let mut source = err.source();
+
+    while let Some(err) = source {
+        let message = err.to_string();
+
+        if !previous.ends_with(&message) {
+            chain.push_str(": caused by: ");
+            chain.push_str(&message);
+        }
+
+        previous = message;
+        source = err.source();
+    }
+
+    chain
+}
+
+/// Extension trait to log when an `Option` is `None`.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use traccia::{LogLevel, OptionLogExt, init_default};
+///
+/// init_default();
+///
+/// let cache: HashMap<&str, u32> = HashMap::new();
+/// let hit = cache.get("key").log_none(LogLevel::Debug, "cache miss");
+///
+/// assert!(hit.is_none());
+/// ```
+pub trait OptionLogExt: Sized {
+    /// Logs `message` at `level` if the value is `None`, then returns it unchanged.
+    ///
+    /// The record carries the file and line of the call site and the active span context.
+    /// As `#[track_caller]` can't provide the module path, the caller's file
+    /// is used as the record target.
+    #[track_caller]
+    fn log_none(self, level: LogLevel, message: impl Into<String>) -> Self;
+}
+
+impl<T> OptionLogExt for Option<T> {
+    #[track_caller]
+    fn log_none(self, level: LogLevel, message: impl Into<String>) -> Self {
+        if self.is_none() {
+            let location = Location::caller();
+            let record = Record::builder(level, message)
+                .target(location.file())
+                .file(location.file())
+                .line(location.line())
+                .context(Record::collect_context(Vec::new()))
+                .build();
+
+            crate::__log(record);
+        }
+
+        self
+    }
+}
+
+/// Renders bytes as an `xxd`-style hex dump.
+///
+/// Each line shows the offset, 16 bytes in groups of two, and their ASCII form,
+/// with a dot for the non printable bytes. Lines are separated by `\n`,
+/// without a trailing one, so the dump stays aligned with [`Newlines::Prefix`](crate::Newlines::Prefix).
+///
+/// Used by the [`hexdump!`](crate::hexdump) macro.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(
+///     traccia::hex_dump(b"Hello, world!\n\x00\x01\x02"),
+///     "00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 0001  Hello, world!...\n\
+///      00000010: 02                                       ."
+/// );
+/// ```
+pub fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let hex = chunk
+                .chunks(2)
+                .map(|pair| {
+                    pair.iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let ascii = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+
+            format!("{:08x}: {:<39}  {}", index * 16, hex, ascii)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error as TracciaError;
+    use std::io;
+
+    #[test]
+    fn test_hex_dump() {
+        assert_eq!(hex_dump(&[]), "");
+
+        let bytes = (0..=32).collect::<Vec<u8>>();
+        let lines = hex_dump(&bytes);
+        let lines = lines.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            [
+                "00000000: 0001 0203 0405 0607 0809 0a0b 0c0d 0e0f  ................",
+                "00000010: 1011 1213 1415 1617 1819 1a1b 1c1d 1e1f  ................",
+                "00000020: 20                                        ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_chain() {
+        let err = TracciaError::TargetWrite {
+            id: crate::TargetId::Custom("audit".to_string()),
+            source: Box::new(TracciaError::Io(io::Error::other("disk full"))),
+        };
+
+        assert_eq!(
+            error_chain(&err),
+            "Failed to write to target custom:audit: caused by: IO error: disk full"
+        );
+
+        let boxed: Box<dyn Error> = Box::new(err);
+        assert!(error_chain(boxed.as_ref()).ends_with(": disk full"));
+    }
+}
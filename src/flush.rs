@@ -0,0 +1,151 @@
+//! Flushing of the records still queued in the logger.
+//!
+//! With the async logger, records are written by a worker thread.
+//! With the `blocking` feature, records are written right away, and flushing
+//! only flushes the targets, so the same calls work with both loggers.
+//!
+//! [`flush`] blocks the calling thread until every record logged before the call
+//! has been written, while [`flush_async`] returns a future that completes at that point,
+//! so async applications don't need to block a runtime thread.
+//!
+//! The future doesn't depend on any specific runtime: the worker thread
+//! wakes the task that polled it last once the queue has been drained.
+
+use crate::LOGGER;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+/// Blocks until all the records logged so far have been written to the targets,
+/// and the targets have been flushed.
+///
+/// Returns immediately if the logger is not initialized.
+///
+/// Do not call this from a hook, as with the async logger hooks run
+/// on the worker thread and would wait for themselves.
+pub fn flush() {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+
+    let pair = Arc::new((Mutex::new(false), Condvar::new()));
+    let signal = Arc::clone(&pair);
+
+    logger.flush(Box::new(move || {
+        let (done, condvar) = &*signal;
+
+        if let Ok(mut done) = done.lock() {
+            *done = true;
+        }
+
+        condvar.notify_all();
+    }));
+
+    let (done, condvar) = &*pair;
+
+    if let Ok(mut done) = done.lock() {
+        while !*done {
+            done = match condvar.wait(done) {
+                Ok(done) => done,
+                Err(_) => return,
+            };
+        }
+    }
+}
+
+/// Returns a future that completes once all the records logged so far
+/// have been written to the targets.
+///
+/// The future completes immediately if the logger is not initialized.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// async fn shutdown() {
+///     traccia::info!("Shutting down");
+///     traccia::flush_async().await;
+/// }
+/// ```
+pub fn flush_async() -> Flush {
+    let state = Arc::new(Mutex::new(FlushState::default()));
+
+    match LOGGER.get() {
+        Some(logger) => {
+            let signal = Arc::clone(&state);
+            logger.flush(Box::new(move || complete(&signal)));
+        }
+
+        None => complete(&state),
+    }
+
+    Flush { state }
+}
+
+#[derive(Default)]
+struct FlushState {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+fn complete(state: &Mutex<FlushState>) {
+    let waker = match state.lock() {
+        Ok(mut state) => {
+            state.done = true;
+            state.waker.take()
+        }
+
+        Err(_) => None,
+    };
+
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+/// Future returned by [`flush_async`].
+pub struct Flush {
+    state: Arc<Mutex<FlushState>>,
+}
+
+impl Future for Flush {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Ok(mut state) = self.state.lock() else {
+            // A poisoned lock means the worker panicked while signaling,
+            // there is nothing left to wait for.
+            return Poll::Ready(());
+        };
+
+        if state.done {
+            return Poll::Ready(());
+        }
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flush_future_completes_after_signal() {
+        let state = Arc::new(Mutex::new(FlushState::default()));
+        let mut future = Flush {
+            state: Arc::clone(&state),
+        };
+
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert!(Pin::new(&mut future).poll(&mut cx).is_pending());
+
+        complete(&state);
+
+        assert!(Pin::new(&mut future).poll(&mut cx).is_ready());
+    }
+}
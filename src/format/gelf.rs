@@ -0,0 +1,215 @@
+use crate::{Clock, Formatter, Record, SystemClock, util};
+use std::{
+    fmt,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Formatter that renders each record as a GELF 1.1 message, as ingested by Graylog.
+///
+/// The message carries the configured host, the record message as `short_message`,
+/// the time of formatting, read from its [`Clock`], and the level as a syslog severity.
+/// The target, the source location, the thread id, the trace id and the span fields
+/// are added as additional fields, prefixed with an underscore:
+///
+/// ```json
+/// {"version":"1.1","host":"api-1","short_message":"Fetching user","timestamp":1712233805.123,"level":6,"_target":"app","_request_user_id":"42"}
+/// ```
+///
+/// Span fields are named `_<span>_<key>`. Characters other than letters, digits,
+/// underscores, dots and dashes are replaced with an underscore, as GELF requires.
+/// A span field named like one of the fields above, such as the `id` field of a `thread`
+/// span, gets a trailing underscore (`_thread_id_`), so it doesn't replace it.
+/// The backtrace, if present, is sent as `full_message`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, GelfFormatter};
+///
+/// let config = Config {
+///     format: Some(Box::new(GelfFormatter::new("api-1"))),
+///     ..Config::default()
+/// };
+/// ```
+#[derive(Clone)]
+pub struct GelfFormatter {
+    host: String,
+    clock: Arc<dyn Clock>,
+}
+
+impl fmt::Debug for GelfFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GelfFormatter")
+            .field("host", &self.host)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GelfFormatter {
+    /// Creates a new GELF formatter, reporting the given host.
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Builder method to set the clock the timestamp is read from,
+    /// [`SystemClock`] by default.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    fn format_at(&self, record: &Record, time: SystemTime) -> String {
+        let mut fields = vec![
+            ("version".to_string(), util::json_string("1.1")),
+            ("host".to_string(), util::json_string(&self.host)),
+            (
+                "short_message".to_string(),
+                util::json_string(&record.message),
+            ),
+        ];
+
+        if let Some(backtrace) = &record.backtrace {
+            fields.push(("full_message".to_string(), util::json_string(backtrace)));
+        }
+
+        fields.push(("timestamp".to_string(), timestamp(time)));
+        fields.push((
+            "level".to_string(),
+            util::syslog_severity(record.level).to_string(),
+        ));
+        fields.push(("_target".to_string(), util::json_string(&record.target)));
+
+        if let Some(module_path) = record.module_path {
+            fields.push(("_module_path".to_string(), util::json_string(module_path)));
+        }
+
+        if let Some(file) = record.file {
+            fields.push(("_file".to_string(), util::json_string(file)));
+        }
+
+        if let Some(line) = record.line {
+            fields.push(("_line".to_string(), line.to_string()));
+        }
+
+        fields.push((
+            "_thread_id".to_string(),
+            util::json_string(&format!("{:?}", record.thread_id)),
+        ));
+
+        if let Some(trace_id) = &record.trace_id {
+            fields.push(("_trace_id".to_string(), util::json_string(trace_id)));
+        }
+
+        for span in &record.context {
+            for (key, value) in &span.fields {
+                let name = field_name(&format!("{}_{}", span.name, key));
+
+                // Inner spans override the fields of the outer ones with the same name
+                match fields.iter_mut().find(|(field, _)| *field == name) {
+                    Some(field) => field.1 = util::json_string(value),
+                    None => fields.push((name, util::json_string(value))),
+                }
+            }
+        }
+
+        let fields = fields
+            .iter()
+            .map(|(key, value)| format!("{}:{}", util::json_string(key), value))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!("{{{}}}", fields)
+    }
+}
+
+/// Additional fields set by the formatter itself, and `_id`, reserved by GELF.
+const RESERVED_FIELDS: [&str; 7] = [
+    "_id",
+    "_target",
+    "_module_path",
+    "_file",
+    "_line",
+    "_thread_id",
+    "_trace_id",
+];
+
+/// Turns a key into a valid GELF additional field name.
+///
+/// Names of reserved fields get a trailing underscore, e.g. `_line_` for `line`.
+fn field_name(key: &str) -> String {
+    let name = key
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '.' | '-' => c,
+            _ => '_',
+        })
+        .collect::<String>();
+
+    let name = format!("_{}", name);
+
+    if RESERVED_FIELDS.contains(&name.as_str()) {
+        name + "_"
+    } else {
+        name
+    }
+}
+
+/// Renders the time elapsed since the epoch, in seconds with milliseconds.
+fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    format!(
+        "{}.{:03}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_millis()
+    )
+}
+
+impl Formatter for GelfFormatter {
+    fn format(&self, record: &Record) -> String {
+        self.format_at(record, self.clock.now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LogLevel, Span};
+    use std::time::Duration;
+
+    #[test]
+    fn test_field_name() {
+        assert_eq!(field_name("request_user id"), "_request_user_id");
+        assert_eq!(field_name("http.method"), "_http.method");
+        assert_eq!(field_name("id"), "_id_");
+        assert_eq!(field_name("thread_id"), "_thread_id_");
+        assert_eq!(field_name("line"), "_line_");
+    }
+
+    #[test]
+    fn test_record() {
+        let record = Record::builder(LogLevel::Warn, "low \"disk\"")
+            .target("app")
+            .line(7)
+            .context(vec![
+                Span::new("request", vec![("user".to_string(), "42".to_string())]),
+                Span::new("request", vec![("user".to_string(), "7".to_string())]),
+                Span::new("thread", vec![("id".to_string(), "main".to_string())]),
+            ])
+            .build();
+
+        let time = UNIX_EPOCH + Duration::from_millis(1_712_233_805_123);
+        let gelf = GelfFormatter::new("api-1")
+            .with_clock(crate::FixedClock::new(time))
+            .format(&record);
+
+        assert!(gelf.starts_with(
+            r#"{"version":"1.1","host":"api-1","short_message":"low \"disk\"","timestamp":1712233805.123,"level":4,"_target":"app","_line":7,"_thread_id":"ThreadId("#
+        ));
+        assert!(gelf.ends_with(r#"","_request_user":"7","_thread_id_":"main"}"#));
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{Formatter, Record, Span, util};
+
+/// Formatter that renders each record as a single line JSON object.
+///
+/// The object contains the level, the message, the target, the source location,
+/// the thread id and, if present, the trace id and the backtrace. Span context is rendered as a nested
+/// `"spans"` object, keyed by span name, so fields can be queried as `spans.request.user_id`:
+///
+/// ```json
+/// {"level":"INFO","message":"Fetching user","target":"app","spans":{"request":{"user_id":"42"},"db":{"table":"users"}}}
+/// ```
+///
+/// Spans sharing the same name (e.g. a span nested in another one with the same name)
+/// are merged into a single object, and the fields of the inner span override
+/// the fields of the outer one with the same key.
+///
+/// Span fields are stored as text, so values are rendered as JSON strings.
+/// Use [`JsonFormatter::infer_types`] to render numbers and booleans as such.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, JsonFormatter};
+///
+/// let config = Config {
+///     format: Some(Box::new(JsonFormatter::new().infer_types(true))),
+///     ..Config::default()
+/// };
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct JsonFormatter {
+    infer_types: bool,
+}
+
+impl JsonFormatter {
+    /// Creates a new JSON formatter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder method to render span field values that look like numbers
+    /// or booleans (`42`, `-1.5`, `true`) without quotes.
+    pub fn infer_types(mut self, infer_types: bool) -> Self {
+        self.infer_types = infer_types;
+        self
+    }
+
+    fn value(&self, value: &str) -> String {
+        if self.infer_types && (value == "true" || value == "false" || is_json_number(value)) {
+            value.to_string()
+        } else {
+            util::json_string(value)
+        }
+    }
+
+    fn spans(&self, context: &[Span]) -> String {
+        let mut merged: Vec<(&str, Vec<(&str, &str)>)> = Vec::new();
+
+        for span in context {
+            let index = match merged.iter().position(|(name, _)| *name == span.name) {
+                Some(index) => index,
+                None => {
+                    merged.push((&span.name, Vec::new()));
+                    merged.len() - 1
+                }
+            };
+
+            let fields = &mut merged[index].1;
+
+            for (key, value) in &span.fields {
+                match fields.iter_mut().find(|(k, _)| k == key) {
+                    Some(field) => field.1 = value,
+                    None => fields.push((key, value)),
+                }
+            }
+        }
+
+        let spans = merged
+            .iter()
+            .map(|(name, fields)| {
+                let fields = fields
+                    .iter()
+                    .map(|(key, value)| format!("{}:{}", util::json_string(key), self.value(value)))
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                format!("{}:{{{}}}", util::json_string(name), fields)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!("{{{}}}", spans)
+    }
+}
+
+/// Returns `true` if the string is a valid JSON number,
+/// e.g. `42`, `-1.5` or `1e3`, but not `007` or `.5`.
+fn is_json_number(s: &str) -> bool {
+    let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+    let s = s.strip_prefix('-').unwrap_or(s);
+
+    let int = digits(s);
+
+    if int == 0 || (int > 1 && s.starts_with('0')) {
+        return false;
+    }
+
+    let mut rest = &s[int..];
+
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let n = digits(fraction);
+
+        if n == 0 {
+            return false;
+        }
+
+        rest = &fraction[n..];
+    }
+
+    if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        let n = digits(exponent);
+
+        if n == 0 {
+            return false;
+        }
+
+        rest = &exponent[n..];
+    }
+
+    rest.is_empty()
+}
+
+impl Formatter for JsonFormatter {
+    fn format(&self, record: &Record) -> String {
+        let mut fields = vec![
+            ("level", util::json_string(&record.level.to_string())),
+            ("message", util::json_string(&record.message)),
+            ("target", util::json_string(&record.target)),
+        ];
+
+        if let Some(module_path) = record.module_path {
+            fields.push(("module_path", util::json_string(module_path)));
+        }
+
+        if let Some(file) = record.file {
+            fields.push(("file", util::json_string(file)));
+        }
+
+        if let Some(line) = record.line {
+            fields.push(("line", line.to_string()));
+        }
+
+        fields.push((
+            "thread_id",
+            util::json_string(&format!("{:?}", record.thread_id)),
+        ));
+
+        if let Some(trace_id) = &record.trace_id {
+            fields.push(("trace_id", util::json_string(trace_id)));
+        }
+
+        if !record.context.is_empty() {
+            fields.push(("spans", self.spans(&record.context)));
+        }
+
+        if let Some(backtrace) = &record.backtrace {
+            fields.push(("backtrace", util::json_string(backtrace)));
+        }
+
+        let fields = fields
+            .into_iter()
+            .map(|(key, value)| format!("\"{}\":{}", key, value))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!("{{{}}}", fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+
+    fn span(name: &str, fields: &[(&str, &str)]) -> Span {
+        Span::new(
+            name,
+            fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_nested_spans() {
+        let context = vec![
+            span("request", &[("user_id", "42"), ("path", "/users")]),
+            span("db", &[("table", "users")]),
+            span("request", &[("user_id", "7")]),
+        ];
+
+        assert_eq!(
+            JsonFormatter::new().spans(&context),
+            r#"{"request":{"user_id":"7","path":"/users"},"db":{"table":"users"}}"#
+        );
+
+        assert_eq!(
+            JsonFormatter::new().infer_types(true).spans(&context),
+            r#"{"request":{"user_id":7,"path":"/users"},"db":{"table":"users"}}"#
+        );
+    }
+
+    #[test]
+    fn test_inferred_values() {
+        let formatter = JsonFormatter::new().infer_types(true);
+
+        assert_eq!(formatter.value("-1.5"), "-1.5");
+        assert_eq!(formatter.value("true"), "true");
+        assert_eq!(formatter.value("007"), "\"007\"");
+        assert_eq!(formatter.value("NaN"), "\"NaN\"");
+        assert_eq!(formatter.value("1e3"), "1e3");
+    }
+
+    #[test]
+    fn test_record() {
+        let record = Record::builder(LogLevel::Info, "say \"hi\"\n")
+            .target("app")
+            .line(3)
+            .context(vec![span("request", &[("id", "1")])])
+            .build();
+
+        let json = JsonFormatter::new().format(&record);
+
+        assert!(json.starts_with(
+            r#"{"level":"INFO","message":"say \"hi\"\n","target":"app","line":3,"thread_id":"ThreadId("#
+        ));
+        assert!(json.ends_with(r#"","spans":{"request":{"id":"1"}}}"#));
+    }
+
+    #[test]
+    fn test_trace_id() {
+        let record = Record::builder(LogLevel::Info, "hi")
+            .trace_id("4bf92f35")
+            .build();
+        let json = JsonFormatter::new().format(&record);
+
+        assert!(json.ends_with(r#"","trace_id":"4bf92f35"}"#));
+    }
+}
@@ -0,0 +1,1347 @@
+/// Formatting utilities for log messages.
+use crate::{Clock, Color, Colorize, Record, Span, Style, SystemClock, error::Error, util};
+use std::{sync::LazyLock, thread::ThreadId};
+
+mod gelf;
+mod json;
+mod multi;
+
+pub use gelf::GelfFormatter;
+pub use json::JsonFormatter;
+pub use multi::MultiFormatter;
+
+/// Position where span context should appear in log messages.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SpanPosition {
+    #[default]
+    /// Span context appears at the end of the message (default).
+    /// Example: `[INFO] message [span: key=value]`
+    End,
+
+    /// Span context appears at the beginning, before the level.
+    /// Example: `[span: key=value] [INFO] message`
+    Start,
+
+    /// Span context appears after the level, before the message.
+    /// Example: `[INFO] [span: key=value] message`
+    AfterLevel,
+
+    /// Span context is not included in the output.
+    None,
+}
+
+/// A part of the line rendered by [`DefaultFormatter`] when a layout is set.
+///
+/// See [`DefaultFormatter::with_layout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Section {
+    /// Time at which the record was formatted, rendered with the formatter's [`TimestampFormat`].
+    /// Example: `2025-04-04T12:30:05.123Z`
+    Timestamp,
+
+    /// The colored level tag.
+    /// Example: `[INFO]`
+    Level,
+
+    /// The module path of the call site.
+    /// Example: `my_app::server`
+    Module,
+
+    /// The id of the thread that emitted the record.
+    /// Example: `ThreadId(1)`
+    ThreadId,
+
+    /// The span context, rendered with the formatter's [`SpanFormat`].
+    /// Example: `[request: id=42]`
+    Spans,
+
+    /// The message of the record.
+    Message,
+
+    /// The file and line of the call site.
+    /// Example: `src/main.rs:42`
+    Location,
+
+    /// The trace id of the record, if any, see [`set_trace_id`](crate::set_trace_id).
+    /// Example: `4bf92f35`
+    TraceId,
+
+    /// A fixed string.
+    Literal(String),
+}
+
+/// Controls how the `Timestamp` section renders the time.
+///
+/// See [`DefaultFormatter::with_timestamp_format`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Wall-clock time read from the formatter clock, as an RFC 3339 UTC timestamp.
+    /// Example: `2025-04-04T12:30:05.123Z`
+    #[default]
+    Rfc3339,
+
+    /// Time elapsed since the logger was initialized, in seconds.
+    /// Measured with a monotonic clock, the formatter clock is not used.
+    /// Example: `[+1.234s]`
+    Elapsed,
+}
+
+impl TimestampFormat {
+    /// Renders the current time, as read from `clock`.
+    fn render(self, clock: &dyn Clock) -> String {
+        match self {
+            TimestampFormat::Rfc3339 => util::rfc3339_utc(clock.now()),
+            TimestampFormat::Elapsed => {
+                format!("[+{:.3}s]", util::start_time().elapsed().as_secs_f64())
+            }
+        }
+    }
+}
+
+/// Controls how line breaks inside a formatted record are written.
+///
+/// A message containing a line break turns a single record into several lines,
+/// which breaks line based parsers. [`JsonFormatter`] and [`GelfFormatter`]
+/// always escape them, so each record takes a single line.
+///
+/// See [`DefaultFormatter::with_newlines`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Newlines {
+    /// Line breaks are written as they are (default).
+    #[default]
+    Keep,
+
+    /// Line breaks are escaped, `\n` and `\r` becoming the two characters `\` and `n` or `r`,
+    /// so each record takes a single line. Backslashes are doubled, so an escaped
+    /// line break can be told apart from a backslash followed by `n` in the message.
+    Escape,
+
+    /// Continuation lines are prefixed with the given string,
+    /// e.g. `"  | "`, so they can be told apart from new records.
+    Prefix(String),
+}
+
+impl Newlines {
+    /// Applies the policy to a formatted record.
+    fn apply(&self, formatted: String) -> String {
+        match self {
+            Newlines::Keep => formatted,
+            Newlines::Escape if formatted.contains(['\\', '\n', '\r']) => formatted
+                .replace('\\', "\\\\")
+                .replace('\r', "\\r")
+                .replace('\n', "\\n"),
+            Newlines::Prefix(prefix) if formatted.contains('\n') => {
+                formatted.replace('\n', &format!("\n{}", prefix))
+            }
+            _ => formatted,
+        }
+    }
+}
+
+/// Controls how the level is rendered.
+///
+/// See [`DefaultFormatter::with_level_style`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LevelStyle {
+    /// The level between square brackets.
+    /// Example: `[INFO]`
+    #[default]
+    Brackets,
+
+    /// The level alone.
+    /// Example: `INFO`
+    Plain,
+}
+
+impl LevelStyle {
+    /// Renders the colored level of the record.
+    fn render(self, record: &Record) -> String {
+        match self {
+            LevelStyle::Brackets => format!("[{}]", record.level.default_coloring()),
+            LevelStyle::Plain => record.level.default_coloring(),
+        }
+    }
+}
+
+/// Controls how much of the module path the `Module` section renders.
+///
+/// See [`DefaultFormatter::with_module_format`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ModuleFormat {
+    /// The full module path.
+    /// Example: `my_app::net::http::handler`
+    #[default]
+    Full,
+
+    /// The crate name only.
+    /// Example: `my_app`
+    Crate,
+
+    /// The last N segments of the module path.
+    /// Example: `http::handler` with `Last(2)`
+    Last(usize),
+}
+
+impl ModuleFormat {
+    /// Returns the part of the record module path to render, if any.
+    fn render(self, record: &Record) -> Option<&'static str> {
+        match self {
+            ModuleFormat::Full => record.module_path,
+            ModuleFormat::Crate => record.crate_name(),
+            ModuleFormat::Last(depth) => record.short_module(depth),
+        }
+    }
+}
+
+/// Controls how span context is rendered.
+///
+/// By default all the fields of a span are rendered in a single
+/// `[span: key=value, key=value]` block, and blocks are separated by a space.
+///
+/// The context can be tinted or dimmed to set it apart from the message on the console.
+/// Like the level colors, the styling is stripped from files.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{DefaultFormatter, SpanFormat};
+///
+/// // Renders `[request: id:42 | user:john] [db: table:users]`
+/// let formatter = DefaultFormatter::new().with_span_format(
+///     SpanFormat::new().field_separator(" | ").key_value_separator(":"),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanFormat {
+    /// Whether the fields of a span are rendered in a single block.
+    /// When `false`, every field gets its own `[span: key=value]` block.
+    pub grouped: bool,
+
+    /// Separator between the fields of a grouped block.
+    pub field_separator: String,
+
+    /// Separator between a field key and its value.
+    pub key_value_separator: String,
+
+    /// Separator between span blocks.
+    pub span_separator: String,
+
+    /// Color of the span context, if any.
+    pub color: Option<Color>,
+
+    /// Whether the span context is dimmed.
+    pub dim: bool,
+}
+
+impl SpanFormat {
+    /// Creates a span format with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the fields of a span are grouped in a single block (default: `true`).
+    pub fn grouped(mut self, grouped: bool) -> Self {
+        self.grouped = grouped;
+        self
+    }
+
+    /// Sets the separator between a field key and its value (default: `"="`).
+    pub fn key_value_separator(mut self, separator: impl Into<String>) -> Self {
+        self.key_value_separator = separator.into();
+        self
+    }
+
+    /// Sets the separator between the fields of a grouped block (default: `", "`).
+    pub fn field_separator(mut self, separator: impl Into<String>) -> Self {
+        self.field_separator = separator.into();
+        self
+    }
+
+    /// Sets the separator between span blocks (default: `" "`).
+    pub fn span_separator(mut self, separator: impl Into<String>) -> Self {
+        self.span_separator = separator.into();
+        self
+    }
+
+    /// Sets the color of the span context (default: none).
+    ///
+    /// (e.g. `color(Color::BrightBlack)` renders the context in gray)
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets whether the span context is dimmed (default: `false`).
+    pub fn dim(mut self, dim: bool) -> Self {
+        self.dim = dim;
+        self
+    }
+
+    /// Applies the color and dimming to the rendered context.
+    fn style(&self, rendered: String) -> String {
+        let rendered = match self.color {
+            Some(color) => rendered.color(color),
+            None => rendered,
+        };
+
+        if self.dim { rendered.dim() } else { rendered }
+    }
+}
+
+impl Default for SpanFormat {
+    fn default() -> Self {
+        Self {
+            grouped: true,
+            field_separator: ", ".to_string(),
+            key_value_separator: "=".to_string(),
+            span_separator: " ".to_string(),
+            color: None,
+            dim: false,
+        }
+    }
+}
+
+/// Defines a log message formatter.
+///
+/// Formatters are responsible for converting a log record into a formatted
+/// string that can be written to output targets.
+pub trait Formatter: Send + Sync {
+    /// Formats a log record into a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The log record to format
+    ///
+    /// # Returns
+    ///
+    /// A formatted string representation of the log record
+    fn format(&self, record: &Record) -> String;
+
+    /// Formats a log record into a string, reporting failures.
+    ///
+    /// The loggers call this method. Formatters that can fail
+    /// (e.g. when serializing structured data) can override it and return
+    /// `Error::Format`, in which case the error is printed to stderr and the
+    /// record is formatted with the `DefaultFormatter` instead, so that it isn't lost.
+    ///
+    /// The default implementation calls `format` and never fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The log record to format
+    ///
+    /// # Returns
+    ///
+    /// A formatted string representation of the log record, or an error
+    fn try_format(&self, record: &Record) -> Result<String, Error> {
+        Ok(self.format(record))
+    }
+}
+
+/// Default log message formatter with configurable span positioning.
+///
+/// Creates log messages in the format: `[LEVEL] message`
+/// with appropriate color coding for the log level and optional span context.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, LogLevel, DefaultFormatter, SpanPosition, Console};
+///
+/// // Default formatter (span at end)
+/// let formatter = DefaultFormatter::new();
+///
+/// // Span at start
+/// let formatter = DefaultFormatter::with_span_at_start();
+///
+/// // Custom position
+/// let formatter = DefaultFormatter::with_position(SpanPosition::AfterLevel);
+///
+/// let config = Config {
+///     level: LogLevel::Info,
+///     targets: vec![Box::new(Console::new())],
+///     format: Some(Box::new(formatter)),
+///     ..Config::default()
+/// };
+/// ```
+pub struct DefaultFormatter {
+    /// The position where span context should appear.
+    pub position: SpanPosition,
+
+    /// How span context is rendered.
+    pub span_format: SpanFormat,
+
+    /// Whether the whole line is colored with the level color,
+    /// instead of the level tag only.
+    pub full_line_color: bool,
+
+    /// Sections rendered in order, instead of the default line.
+    /// When set, `position` is ignored.
+    pub layout: Option<Vec<Section>>,
+
+    /// How much of the module path the `Module` section renders.
+    pub module_format: ModuleFormat,
+
+    /// How the `Timestamp` section renders the time.
+    pub timestamp_format: TimestampFormat,
+
+    /// How the level is rendered.
+    pub level_style: LevelStyle,
+
+    /// String placed between the parts of the line, a space by default.
+    pub separator: String,
+
+    /// How line breaks inside the formatted record are written.
+    pub newlines: Newlines,
+
+    /// String repeated before the message once per active span, if any.
+    pub span_indent: Option<String>,
+
+    /// Clock the `Timestamp` section reads the time from.
+    pub clock: Box<dyn Clock>,
+}
+
+impl DefaultFormatter {
+    /// Creates a new formatter with the specified span position.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Where to place span context in the log message
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{DefaultFormatter, SpanPosition};
+    ///
+    /// let formatter = DefaultFormatter::with_position(SpanPosition::Start);
+    /// ```
+    pub fn with_position(position: SpanPosition) -> Self {
+        Self {
+            position,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new formatter with default settings (span at end).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::DefaultFormatter;
+    ///
+    /// let formatter = DefaultFormatter::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a formatter with span context at the start.
+    ///
+    /// Output format: `[span: key=value] [LEVEL] message`
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::DefaultFormatter;
+    ///
+    /// let formatter = DefaultFormatter::with_span_at_start();
+    /// ```
+    pub fn with_span_at_start() -> Self {
+        Self::with_position(SpanPosition::Start)
+    }
+
+    /// Creates a formatter with span context after the level.
+    ///
+    /// Output format: `[LEVEL] [span: key=value] message`
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::DefaultFormatter;
+    ///
+    /// let formatter = DefaultFormatter::with_span_after_level();
+    /// ```
+    pub fn with_span_after_level() -> Self {
+        Self::with_position(SpanPosition::AfterLevel)
+    }
+
+    /// Creates a formatter with span context at the end (default).
+    ///
+    /// Output format: `[LEVEL] message [span: key=value]`
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::DefaultFormatter;
+    ///
+    /// let formatter = DefaultFormatter::with_span_at_end();
+    /// ```
+    pub fn with_span_at_end() -> Self {
+        Self::with_position(SpanPosition::End)
+    }
+
+    /// Creates a formatter without span context.
+    ///
+    /// Output format: `[LEVEL] message`
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::DefaultFormatter;
+    ///
+    /// let formatter = DefaultFormatter::without_span();
+    /// ```
+    pub fn without_span() -> Self {
+        Self::with_position(SpanPosition::None)
+    }
+
+    /// Builder method to set how span context is rendered.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{DefaultFormatter, SpanFormat};
+    ///
+    /// let formatter = DefaultFormatter::new().with_span_format(SpanFormat::new().grouped(true));
+    /// ```
+    pub fn with_span_format(mut self, span_format: SpanFormat) -> Self {
+        self.span_format = span_format;
+        self
+    }
+
+    /// Builder method to color the whole line with the level color.
+    ///
+    /// Colored substrings in the message keep their color,
+    /// and the line color resumes right after them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::DefaultFormatter;
+    ///
+    /// // Error lines are entirely red, warning lines entirely yellow, and so on.
+    /// let formatter = DefaultFormatter::new().with_full_line_color();
+    /// ```
+    pub fn with_full_line_color(mut self) -> Self {
+        self.full_line_color = true;
+        self
+    }
+
+    /// Builder method to render the given sections in order, separated by a space.
+    ///
+    /// Sections with nothing to show (e.g. `Spans` without active spans,
+    /// or `Module` for a record without module path) are skipped.
+    /// The span position is ignored, use the `Spans` section to place the span context.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{DefaultFormatter, Section};
+    ///
+    /// // Renders `2025-04-04T12:30:05.123Z [INFO] my_app::server Listening [server: port=80]`
+    /// let formatter = DefaultFormatter::new().with_layout(&[
+    ///     Section::Timestamp,
+    ///     Section::Level,
+    ///     Section::Module,
+    ///     Section::Message,
+    ///     Section::Spans,
+    /// ]);
+    /// ```
+    pub fn with_layout(mut self, layout: &[Section]) -> Self {
+        self.layout = Some(layout.to_vec());
+        self
+    }
+
+    /// Builder method to shorten the module path rendered by the `Module` section.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{DefaultFormatter, ModuleFormat, Section};
+    ///
+    /// // Renders `[INFO] http::handler Request handled`
+    /// // instead of `[INFO] my_app::net::http::handler Request handled`
+    /// let formatter = DefaultFormatter::new()
+    ///     .with_layout(&[Section::Level, Section::Module, Section::Message])
+    ///     .with_module_format(ModuleFormat::Last(2));
+    /// ```
+    pub fn with_module_format(mut self, module_format: ModuleFormat) -> Self {
+        self.module_format = module_format;
+        self
+    }
+
+    /// Builder method to set how the `Timestamp` section renders the time.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{DefaultFormatter, Section, TimestampFormat};
+    ///
+    /// // Renders `[+0.123s] [INFO] Config loaded`
+    /// let formatter = DefaultFormatter::new()
+    ///     .with_layout(&[Section::Timestamp, Section::Level, Section::Message])
+    ///     .with_timestamp_format(TimestampFormat::Elapsed);
+    /// ```
+    pub fn with_timestamp_format(mut self, timestamp_format: TimestampFormat) -> Self {
+        self.timestamp_format = timestamp_format;
+        self
+    }
+
+    /// Builder method to set the clock the `Timestamp` section reads the time from,
+    /// [`SystemClock`] by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use std::time::UNIX_EPOCH;
+    /// use traccia::{DefaultFormatter, FixedClock, Section};
+    ///
+    /// // Renders `1970-01-01T00:00:00.000Z [INFO] Config loaded`, for reproducible tests
+    /// let formatter = DefaultFormatter::new()
+    ///     .with_layout(&[Section::Timestamp, Section::Level, Section::Message])
+    ///     .with_clock(FixedClock::new(UNIX_EPOCH));
+    /// ```
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Builder method to set how the level is rendered, with or without brackets.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{DefaultFormatter, LevelStyle};
+    ///
+    /// // Renders `INFO Server started`
+    /// let formatter = DefaultFormatter::new().with_level_style(LevelStyle::Plain);
+    /// ```
+    pub fn with_level_style(mut self, level_style: LevelStyle) -> Self {
+        self.level_style = level_style;
+        self
+    }
+
+    /// Builder method to set the string placed between the parts of the line
+    /// (the level, the message and the span context, or the sections of the layout).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{DefaultFormatter, LevelStyle};
+    ///
+    /// // Renders `INFO | Server started`
+    /// let formatter = DefaultFormatter::new()
+    ///     .with_level_style(LevelStyle::Plain)
+    ///     .with_separator(" | ");
+    /// ```
+    pub fn with_separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Builder method to set how line breaks inside a record are written,
+    /// in the message, the span fields or the backtrace.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::{DefaultFormatter, Formatter, LogLevel, Newlines, Record};
+    ///
+    /// // Each record takes exactly one line
+    /// let formatter = DefaultFormatter::new().with_newlines(Newlines::Escape);
+    ///
+    /// let record = Record::builder(LogLevel::Info, "first\nsecond").build();
+    ///
+    /// assert!(formatter.format(&record).ends_with("first\\nsecond"));
+    /// ```
+    pub fn with_newlines(mut self, newlines: Newlines) -> Self {
+        self.newlines = newlines;
+        self
+    }
+
+    /// Builder method to indent the message once per active span,
+    /// as counted by [`Record::span_depth`], giving a tree view of nested spans.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::DefaultFormatter;
+    ///
+    /// // [INFO] Handling request [request: id=1]
+    /// // [INFO]   Querying [request: id=1] [db: table=users]
+    /// let formatter = DefaultFormatter::new().with_span_indent("  ");
+    /// ```
+    pub fn with_span_indent(mut self, indent: impl Into<String>) -> Self {
+        self.span_indent = Some(indent.into());
+        self
+    }
+
+    /// Returns the message of the record, indented if `span_indent` is set.
+    fn message(&self, record: &Record) -> String {
+        match &self.span_indent {
+            Some(indent) => indent.repeat(record.span_depth()) + &record.message,
+            None => record.message.clone(),
+        }
+    }
+
+    /// Renders the level, message and span context of a record on a single line.
+    fn format_line(&self, record: &Record, span_str: &str) -> String {
+        let level_str = self.level_style.render(record);
+        let message = self.message(record);
+        let message = message.as_str();
+
+        let parts = match (self.position, span_str.is_empty()) {
+            (SpanPosition::None, _) | (_, true) => vec![level_str.as_str(), message],
+            (SpanPosition::End, false) => vec![level_str.as_str(), message, span_str],
+            (SpanPosition::Start, false) => vec![span_str, level_str.as_str(), message],
+            (SpanPosition::AfterLevel, false) => vec![level_str.as_str(), span_str, message],
+        };
+
+        parts.join(&self.separator)
+    }
+
+    /// Renders the sections of a layout on a single line, skipping the empty ones.
+    fn format_layout(&self, record: &Record, layout: &[Section], span_str: &str) -> String {
+        layout
+            .iter()
+            .filter_map(|section| {
+                let rendered = match section {
+                    Section::Timestamp => self.timestamp_format.render(self.clock.as_ref()),
+                    Section::Level => self.level_style.render(record),
+                    Section::Module => self
+                        .module_format
+                        .render(record)
+                        .unwrap_or_default()
+                        .to_string(),
+                    Section::ThreadId => format!("{:?}", record.thread_id),
+                    Section::Spans => span_str.to_string(),
+                    Section::Message => self.message(record),
+                    Section::Location => match (record.file, record.line) {
+                        (Some(file), Some(line)) => format!("{}:{}", file, line),
+                        (Some(file), None) => file.to_string(),
+                        _ => String::new(),
+                    },
+                    Section::TraceId => record.trace_id.clone().unwrap_or_default(),
+                    Section::Literal(literal) => literal.clone(),
+                };
+
+                (!rendered.is_empty()).then_some(rendered)
+            })
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+}
+
+/// Formatter used when a [`Config`](crate::Config) has none, and as a fallback
+/// when a formatter fails.
+pub(crate) static DEFAULT_FORMATTER: LazyLock<DefaultFormatter> =
+    LazyLock::new(DefaultFormatter::new);
+
+impl Default for DefaultFormatter {
+    fn default() -> Self {
+        Self {
+            position: SpanPosition::End,
+            span_format: SpanFormat::default(),
+            full_line_color: false,
+            layout: None,
+            module_format: ModuleFormat::Full,
+            timestamp_format: TimestampFormat::Rfc3339,
+            level_style: LevelStyle::Brackets,
+            separator: " ".to_string(),
+            newlines: Newlines::Keep,
+            span_indent: None,
+            clock: Box::new(SystemClock),
+        }
+    }
+}
+
+impl Formatter for DefaultFormatter {
+    /// Formats a log record using the configured format.
+    ///
+    /// The format includes the log level (color-coded) and message,
+    /// with span context positioned according to the formatter's configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The log record to format
+    ///
+    /// # Returns
+    ///
+    /// A formatted string representation of the log record
+    fn format(&self, record: &Record) -> String {
+        let span_str = format_span_context_styled(&record.context, &self.span_format);
+        let line = match &self.layout {
+            Some(layout) => self.format_layout(record, layout, &span_str),
+            None => self.format_line(record, &span_str),
+        };
+        let formatted = self.newlines.apply(with_backtrace(line, record));
+
+        if self.full_line_color {
+            formatted.color(record.level.default_color())
+        } else {
+            formatted
+        }
+    }
+}
+
+/// Renders a thread id as its number, e.g. `3` for `ThreadId(3)`.
+///
+/// The number of a `ThreadId` is not public, so it is read from its `Debug` output.
+/// If that output ever stops containing a number, it is returned as it is.
+///
+/// # Examples
+///
+/// ```
+/// let id = std::thread::current().id();
+/// let rendered = traccia::format_thread_id(id);
+///
+/// assert!(rendered.parse::<u64>().is_ok());
+/// assert_eq!(format!("{:?}", id), format!("ThreadId({})", rendered));
+/// ```
+pub fn format_thread_id(id: ThreadId) -> String {
+    let debug = format!("{:?}", id);
+
+    let number = debug
+        .split(|c: char| !c.is_ascii_digit())
+        .find(|part| !part.is_empty());
+
+    match number {
+        Some(number) => number.to_string(),
+        None => debug,
+    }
+}
+
+/// Formats span context into a string with default formatting.
+///
+/// Each span is formatted as `[span_name: key=value, key=value]`
+/// and multiple spans are joined with spaces.
+///
+/// # Arguments
+///
+/// * `context` - Slice of active spans, from the outermost to the innermost
+///
+/// # Returns
+///
+/// A formatted string of all span contexts, or an empty string if no context exists
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Span, format_span_context};
+///
+/// let context = vec![
+///     Span::new("request", vec![("id".to_string(), "123".to_string())]),
+///     Span::new("user", vec![("name".to_string(), "john".to_string())]),
+/// ];
+/// let span_str = format_span_context(&context);
+/// // Returns: "[request: id=123] [user: name=john]"
+/// ```
+pub fn format_span_context(context: &[Span]) -> String {
+    format_span_context_styled(context, &SpanFormat::default())
+}
+
+/// Formats span context according to a [`SpanFormat`].
+///
+/// Spans without fields are rendered as `[span_name]` when grouping is enabled,
+/// and omitted otherwise.
+///
+/// # Arguments
+///
+/// * `context` - Slice of active spans, from the outermost to the innermost
+/// * `span_format` - How blocks and fields are separated and grouped
+///
+/// # Returns
+///
+/// A formatted string of all span contexts, or an empty string if no context exists
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Span, SpanFormat, format_span_context_styled};
+///
+/// let context = vec![Span::new(
+///     "request",
+///     vec![
+///         ("id".to_string(), "123".to_string()),
+///         ("user".to_string(), "john".to_string()),
+///     ],
+/// )];
+/// let span_str = format_span_context_styled(&context, &SpanFormat::new().grouped(false));
+/// // Returns: "[request: id=123] [request: user=john]"
+/// ```
+pub fn format_span_context_styled(context: &[Span], span_format: &SpanFormat) -> String {
+    let field = |(key, value): &(String, String)| {
+        format!("{}{}{}", key, span_format.key_value_separator, value)
+    };
+
+    let blocks: Vec<String> = if span_format.grouped {
+        context
+            .iter()
+            .map(|span| {
+                if span.fields.is_empty() {
+                    return format!("[{}]", span.name);
+                }
+
+                let fields = span.fields.iter().map(field).collect::<Vec<_>>();
+                format!(
+                    "[{}: {}]",
+                    span.name,
+                    fields.join(&span_format.field_separator)
+                )
+            })
+            .collect()
+    } else {
+        context
+            .iter()
+            .flat_map(|span| {
+                span.fields
+                    .iter()
+                    .map(move |f| format!("[{}: {}]", span.name, field(f)))
+            })
+            .collect()
+    };
+
+    if blocks.is_empty() {
+        return String::new();
+    }
+
+    span_format.style(blocks.join(&span_format.span_separator))
+}
+
+/// Formats span context with a custom formatter function.
+///
+/// This allows you to customize how each individual span is formatted.
+///
+/// # Arguments
+///
+/// * `context` - Slice of active spans, from the outermost to the innermost
+/// * `formatter` - Function that takes (span_name, fields) and returns a formatted string,
+///   the fields are rendered as `key=value` pairs separated by `, `
+///
+/// # Returns
+///
+/// A formatted string with custom-formatted spans joined by spaces
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Span, format_span_context_with};
+///
+/// let context = vec![Span::new("request", vec![("id".to_string(), "123".to_string())])];
+/// let span_str = format_span_context_with(&context, |name, fields| {
+///     format!("{}({})", name, fields)
+/// });
+/// // Returns: "request(id=123)"
+/// ```
+pub fn format_span_context_with<F>(context: &[Span], formatter: F) -> String
+where
+    F: Fn(&str, &str) -> String,
+{
+    if context.is_empty() {
+        return String::new();
+    }
+
+    context
+        .iter()
+        .map(|span| {
+            let fields = span
+                .fields
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            formatter(&span.name, &fields)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Helper builder for creating custom formatters with span positioning support.
+///
+/// This builder makes it easy to create custom formatters that support span positioning
+/// without reimplementing the span positioning logic.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{FormatterBuilder, SpanPosition};
+///
+/// // Create a formatter with timestamp and custom level format
+/// let formatter = FormatterBuilder::new()
+///     .with_span_position(SpanPosition::AfterLevel)
+///     .build(|record, span_str| {
+///         let timestamp = chrono::Local::now().format("%H:%M:%S");
+///         let level = record.level.to_string().to_uppercase();
+///
+///         if span_str.is_empty() {
+///             format!("[{}] {} {}", timestamp, level, record.message)
+///         } else {
+///             format!("[{}] {} {} {}", timestamp, level, span_str, record.message)
+///         }
+///     });
+/// ```
+pub struct FormatterBuilder {
+    span_position: SpanPosition,
+    span_format: SpanFormat,
+}
+
+impl FormatterBuilder {
+    /// Creates a new formatter builder with default span position (End).
+    pub fn new() -> Self {
+        Self {
+            span_position: SpanPosition::End,
+            span_format: SpanFormat::default(),
+        }
+    }
+
+    /// Sets the span position for the formatter.
+    pub fn with_span_position(mut self, position: SpanPosition) -> Self {
+        self.span_position = position;
+        self
+    }
+
+    /// Sets how the span context passed to the formatting function is rendered.
+    pub fn with_span_format(mut self, span_format: SpanFormat) -> Self {
+        self.span_format = span_format;
+        self
+    }
+
+    /// Builds a custom formatter with the specified formatting function.
+    ///
+    /// The formatting function receives:
+    /// - `record`: The log record to format
+    /// - `span_str`: Pre-formatted span context string (may be empty)
+    ///
+    /// You are responsible for combining these elements in your desired format.
+    ///
+    /// # Arguments
+    ///
+    /// * `format_fn` - Function that takes (record, span_str) and returns a formatted string
+    pub fn build<F>(self, format_fn: F) -> CustomFormatter<F>
+    where
+        F: Fn(&Record, &str) -> String + Send + Sync,
+    {
+        CustomFormatter {
+            span_position: self.span_position,
+            span_format: self.span_format,
+            format_fn,
+        }
+    }
+}
+
+impl Default for FormatterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A custom formatter created by `FormatterBuilder`.
+///
+/// This formatter handles span positioning automatically while allowing
+/// custom formatting logic for the rest of the log message.
+pub struct CustomFormatter<F>
+where
+    F: Fn(&Record, &str) -> String + Send + Sync,
+{
+    span_position: SpanPosition,
+    span_format: SpanFormat,
+    format_fn: F,
+}
+
+impl<F> Formatter for CustomFormatter<F>
+where
+    F: Fn(&Record, &str) -> String + Send + Sync,
+{
+    fn format(&self, record: &Record) -> String {
+        let span_str = format_span_context_styled(&record.context, &self.span_format);
+
+        match self.span_position {
+            SpanPosition::None => {
+                // Don't pass span string to format function
+                (self.format_fn)(record, "")
+            }
+            _ => {
+                // Pass span string and let format function handle positioning
+                (self.format_fn)(record, &span_str)
+            }
+        }
+    }
+}
+
+/// Helper function to format a record with span context at the specified position.
+///
+/// This utility function implements the standard span positioning logic.
+/// Use this in custom formatters to get consistent span positioning behavior.
+///
+/// # Arguments
+///
+/// * `record` - The log record to format
+/// * `position` - Where to place the span context
+///
+/// # Returns
+///
+/// A formatted string with the level, message, and span positioned according to `position`.
+/// If the record carries a backtrace, it is appended below, indented by four spaces.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Formatter, Record, SpanPosition, format_with_span_position};
+///
+/// struct MyFormatter;
+///
+/// impl Formatter for MyFormatter {
+///     fn format(&self, record: &Record) -> String {
+///         // Use the helper to get standard formatting with spans after level
+///         format_with_span_position(record, SpanPosition::AfterLevel)
+///     }
+/// }
+/// ```
+pub fn format_with_span_position(record: &Record, position: SpanPosition) -> String {
+    let line = DefaultFormatter::with_position(position)
+        .format_line(record, &format_span_context(&record.context));
+
+    with_backtrace(line, record)
+}
+
+/// Appends the backtrace of the record, if any, below the line.
+fn with_backtrace(mut line: String, record: &Record) -> String {
+    if let Some(backtrace) = &record.backtrace {
+        for frame in backtrace.lines() {
+            line.push_str("\n    ");
+            line.push_str(frame);
+        }
+    }
+
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+    use std::time::SystemTime;
+
+    fn context() -> Vec<Span> {
+        vec![
+            Span::new(
+                "request",
+                vec![
+                    ("id".to_string(), "42".to_string()),
+                    ("user".to_string(), "john".to_string()),
+                ],
+            ),
+            Span::new("db", vec![("table".to_string(), "users".to_string())]),
+        ]
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn test_full_line_color() {
+        let record = Record::builder(LogLevel::Error, "failed").build();
+        let formatted = DefaultFormatter::new()
+            .with_full_line_color()
+            .format(&record);
+
+        assert_eq!(
+            formatted,
+            "\x1b[31m[\x1b[31mERROR\x1b[39m\x1b[31m] failed\x1b[39m"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn test_span_style() {
+        let span_format = SpanFormat::new().color(Color::BrightBlack).dim(true);
+
+        assert_eq!(
+            format_span_context_styled(&context()[..1], &span_format),
+            "\x1b[2m\x1b[90m[request: id=42, user=john]\x1b[39m\x1b[22m"
+        );
+        assert_eq!(format_span_context_styled(&[], &span_format), "");
+    }
+
+    #[test]
+    fn test_layout() {
+        let record = Record::builder(LogLevel::Info, "listening")
+            .module_path("app::server")
+            .file("src/server.rs")
+            .line(12)
+            .build();
+
+        let formatter = DefaultFormatter::new().with_layout(&[
+            Section::Literal("app".to_string()),
+            Section::Location,
+            Section::Level,
+            Section::Spans,
+            Section::Module,
+            Section::Message,
+        ]);
+
+        assert_eq!(
+            util::strip_ansi_codes(&formatter.format(&record)),
+            "app src/server.rs:12 [INFO] app::server listening"
+        );
+
+        assert_eq!(
+            util::strip_ansi_codes(
+                &formatter
+                    .with_module_format(ModuleFormat::Crate)
+                    .format(&record)
+            ),
+            "app src/server.rs:12 [INFO] app listening"
+        );
+    }
+
+    #[test]
+    fn test_elapsed_timestamp() {
+        let formatter = DefaultFormatter::new()
+            .with_layout(&[Section::Timestamp, Section::Message])
+            .with_timestamp_format(TimestampFormat::Elapsed);
+
+        let formatted = formatter.format(&Record::builder(LogLevel::Info, "ready").build());
+        let elapsed = formatted
+            .strip_prefix("[+")
+            .and_then(|rest| rest.strip_suffix("s] ready"))
+            .unwrap();
+
+        assert!(elapsed.parse::<f64>().is_ok());
+        assert_eq!(elapsed.split('.').nth(1).map(str::len), Some(3));
+    }
+
+    #[test]
+    fn test_newlines() {
+        let record = Record::builder(LogLevel::Info, "first\nsecond").build();
+
+        let format = |newlines| {
+            util::strip_ansi_codes(
+                &DefaultFormatter::new()
+                    .with_newlines(newlines)
+                    .format(&record),
+            )
+        };
+
+        assert_eq!(format(Newlines::Keep), "[INFO] first\nsecond");
+        assert_eq!(format(Newlines::Escape), "[INFO] first\\nsecond");
+
+        let record = Record::builder(LogLevel::Info, "C:\\new\nline").build();
+        let formatted = DefaultFormatter::new()
+            .with_newlines(Newlines::Escape)
+            .format(&record);
+
+        assert_eq!(
+            util::strip_ansi_codes(&formatted),
+            "[INFO] C:\\\\new\\nline"
+        );
+        assert_eq!(
+            format(Newlines::Prefix("  | ".to_string())),
+            "[INFO] first\n  | second"
+        );
+    }
+
+    #[test]
+    fn test_span_indent() {
+        let span = |name: &str| Span::new(name, Vec::new());
+        let record = Record::builder(LogLevel::Info, "query")
+            .context(vec![
+                Span::synthetic("global", Vec::new()),
+                span("request"),
+                span("db"),
+                Span::synthetic("fields", Vec::new()),
+            ])
+            .build();
+
+        let formatter = DefaultFormatter::without_span().with_span_indent("  ");
+
+        assert_eq!(record.span_depth(), 2);
+        assert_eq!(
+            util::strip_ansi_codes(&formatter.format(&record)),
+            "[INFO]     query"
+        );
+        assert_eq!(
+            formatter
+                .with_layout(&[Section::Message])
+                .format(&Record::builder(LogLevel::Info, "top").build()),
+            "top"
+        );
+    }
+
+    #[test]
+    fn test_level_style_and_separator() {
+        let record = Record::builder(LogLevel::Warn, "disk almost full")
+            .context(vec![Span::new(
+                "disk",
+                vec![("path".to_string(), "/".to_string())],
+            )])
+            .build();
+
+        let formatter = DefaultFormatter::new()
+            .with_level_style(LevelStyle::Plain)
+            .with_separator(" | ");
+
+        assert_eq!(
+            util::strip_ansi_codes(&formatter.format(&record)),
+            "WARN | disk almost full | [disk: path=/]"
+        );
+
+        assert_eq!(
+            util::strip_ansi_codes(&DefaultFormatter::new().format(&record)),
+            "[WARN] disk almost full [disk: path=/]"
+        );
+    }
+
+    #[test]
+    fn test_clock() {
+        let formatter = DefaultFormatter::new()
+            .with_layout(&[Section::Timestamp, Section::Message])
+            .with_clock(crate::FixedClock::new(
+                SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(1_709_210_096_789),
+            ));
+
+        let record = Record::builder(LogLevel::Info, "ready").build();
+
+        assert_eq!(formatter.format(&record), "2024-02-29T12:34:56.789Z ready");
+        assert_eq!(formatter.format(&record), formatter.format(&record));
+    }
+
+    #[test]
+    fn test_span_context_grouped_by_default() {
+        assert_eq!(
+            format_span_context(&context()),
+            "[request: id=42, user=john] [db: table=users]"
+        );
+    }
+
+    #[test]
+    fn test_span_context_separators() {
+        let span_format = SpanFormat::new()
+            .field_separator(" | ")
+            .key_value_separator(":")
+            .span_separator(" - ");
+
+        assert_eq!(
+            format_span_context_styled(&context(), &span_format),
+            "[request: id:42 | user:john] - [db: table:users]"
+        );
+    }
+
+    #[test]
+    fn test_span_context_ungrouped() {
+        assert_eq!(
+            format_span_context_styled(&context(), &SpanFormat::new().grouped(false)),
+            "[request: id=42] [request: user=john] [db: table=users]"
+        );
+    }
+
+    #[test]
+    fn test_backtrace_is_indented_below_message() {
+        let record = Record::builder(LogLevel::Error, "failed")
+            .backtrace("0: main\n1: start")
+            .build();
+
+        let formatted = util::strip_ansi_codes(&DefaultFormatter::new().format(&record));
+
+        assert_eq!(formatted, "[ERROR] failed\n    0: main\n    1: start");
+    }
+}
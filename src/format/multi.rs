@@ -0,0 +1,106 @@
+use crate::{Formatter, LogLevel, Record, error::Error};
+use std::collections::HashMap;
+
+/// Formatter that picks a different formatter for some levels.
+///
+/// Records with a level that has an override are formatted by it,
+/// the others by the default formatter. This allows e.g. formatting errors
+/// with their location and backtrace while keeping the other lines short.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, DefaultFormatter, LogLevel, MultiFormatter, Section};
+///
+/// let verbose = || {
+///     DefaultFormatter::new().with_layout(&[Section::Level, Section::Location, Section::Message])
+/// };
+///
+/// let config = Config {
+///     format: Some(Box::new(
+///         MultiFormatter::new(DefaultFormatter::new())
+///             .with_level(LogLevel::Error, verbose())
+///             .with_level(LogLevel::Fatal, verbose()),
+///     )),
+///     capture_backtrace_at: Some(LogLevel::Error),
+///     ..Config::default()
+/// };
+/// ```
+pub struct MultiFormatter {
+    default: Box<dyn Formatter>,
+    overrides: HashMap<LogLevel, Box<dyn Formatter>>,
+}
+
+impl MultiFormatter {
+    /// Creates a formatter using `default` for every level.
+    pub fn new(default: impl Formatter + 'static) -> Self {
+        Self {
+            default: Box::new(default),
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Builder method to format the records of `level` with `formatter`.
+    ///
+    /// Setting an override for a level twice replaces the first one.
+    pub fn with_level(mut self, level: LogLevel, formatter: impl Formatter + 'static) -> Self {
+        self.overrides.insert(level, Box::new(formatter));
+        self
+    }
+
+    fn formatter_for(&self, level: LogLevel) -> &dyn Formatter {
+        self.overrides
+            .get(&level)
+            .map_or(self.default.as_ref(), |formatter| formatter.as_ref())
+    }
+}
+
+impl Formatter for MultiFormatter {
+    fn format(&self, record: &Record) -> String {
+        self.formatter_for(record.level).format(record)
+    }
+
+    /// Forwards to the `try_format` of the selected formatter,
+    /// so its errors are reported as usual.
+    fn try_format(&self, record: &Record) -> Result<String, Error> {
+        self.formatter_for(record.level).try_format(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Prefix(&'static str);
+
+    impl Formatter for Prefix {
+        fn format(&self, record: &Record) -> String {
+            format!("{}: {}", self.0, record.message)
+        }
+    }
+
+    struct Failing;
+
+    impl Formatter for Failing {
+        fn format(&self, _: &Record) -> String {
+            String::new()
+        }
+
+        fn try_format(&self, _: &Record) -> Result<String, Error> {
+            Err(Error::Format("unsupported".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_dispatch_on_level() {
+        let formatter = MultiFormatter::new(Prefix("short"))
+            .with_level(LogLevel::Error, Prefix("verbose"))
+            .with_level(LogLevel::Fatal, Failing);
+
+        let format = |level| formatter.try_format(&Record::builder(level, "hi").build());
+
+        assert_eq!(format(LogLevel::Info).unwrap(), "short: hi");
+        assert_eq!(format(LogLevel::Error).unwrap(), "verbose: hi");
+        assert!(matches!(format(LogLevel::Fatal), Err(Error::Format(_))));
+    }
+}
@@ -1,7 +1,17 @@
-use crate::{LogLevel, target::TargetId};
-use std::sync::{LazyLock, RwLock};
+use crate::{LogLevel, Record, Span, target::TargetId};
+use std::{
+    cell::Cell,
+    sync::{
+        LazyLock, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
 type HookCallback = Box<dyn Fn(LogLevel, &TargetId) + Send + Sync>;
+type RecordHookCallback = Box<dyn Fn(&Record, &str, &TargetId) + Send + Sync>;
+type SpanEnterCallback = Box<dyn Fn(&Span) + Send + Sync>;
+type SpanExitCallback = Box<dyn Fn(&Span, Duration) + Send + Sync>;
 
 /// Represents a hook that can be set to trigger
 /// at specific points in the logging process.
@@ -13,15 +23,72 @@ type HookCallback = Box<dyn Fn(LogLevel, &TargetId) + Send + Sync>;
 /// Hooks are executed in the order they are added.
 pub enum Hook {
     /// Hook that is called before a log message is written.
+    ///
+    /// Use `BeforeLogRecord` to get the record and the message too.
     BeforeLog(HookCallback),
     /// Hook that is called after a log message is written.
     /// This is useful for post-processing or additional actions.
+    ///
+    /// Use `AfterLogRecord` to get the record and the message too.
     AfterLog(HookCallback),
+    /// Hook that is called before a log message is written,
+    /// with the full record and its formatted message.
+    ///
+    /// The message is the one written to the target: colors are already stripped
+    /// for the targets that don't want them, such as files.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{Hook, LogLevel};
+    ///
+    /// traccia::set_hook(Hook::BeforeLogRecord(Box::new(|record, formatted, _| {
+    ///     if record.level >= LogLevel::Error {
+    ///         report_error(&record.message, formatted);
+    ///     }
+    /// })));
+    /// ```
+    BeforeLogRecord(RecordHookCallback),
+    /// Hook that is called after a log message is written,
+    /// with the full record and its formatted message, as with `BeforeLogRecord`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{Hook, LogLevel, TargetId};
+    ///
+    /// traccia::set_hook(Hook::AfterLogRecord(Box::new(|record, formatted, target| {
+    ///     if record.level >= LogLevel::Error && matches!(target, TargetId::File(_)) {
+    ///         notify("Error logged", formatted);
+    ///     }
+    /// })));
+    /// ```
+    AfterLogRecord(RecordHookCallback),
+    /// Hook that is called when a span is entered, on the thread entering it.
+    SpanEnter(SpanEnterCallback),
+    /// Hook that is called when a span is exited, on the thread exiting it,
+    /// with the time elapsed since it was entered.
+    ///
+    /// Together with `SpanEnter`, this allows building instrumentation backends,
+    /// e.g. a profiler recording the duration of each span.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::Hook;
+    ///
+    /// traccia::set_hook(Hook::SpanExit(Box::new(|span, elapsed| {
+    ///     record_timing(&span.name, elapsed);
+    /// })));
+    /// ```
+    SpanExit(SpanExitCallback),
 }
 
 pub struct HookSystem {
     before_log_hooks: Vec<Hook>,
     after_log_hooks: Vec<Hook>,
+    span_enter_hooks: Vec<SpanEnterCallback>,
+    span_exit_hooks: Vec<SpanExitCallback>,
 }
 
 impl HookSystem {
@@ -29,36 +96,142 @@ impl HookSystem {
         Self {
             before_log_hooks: Vec::new(),
             after_log_hooks: Vec::new(),
+            span_enter_hooks: Vec::new(),
+            span_exit_hooks: Vec::new(),
         }
     }
 
-    /// Splits the hooks into two separate vectors
+    /// Splits the hooks into separate vectors
     pub fn add_hook(&mut self, hook: Hook) {
         match hook {
-            Hook::BeforeLog(_) => self.before_log_hooks.push(hook),
-            Hook::AfterLog(_) => self.after_log_hooks.push(hook),
+            Hook::BeforeLog(_) | Hook::BeforeLogRecord(_) => self.before_log_hooks.push(hook),
+            Hook::AfterLog(_) | Hook::AfterLogRecord(_) => self.after_log_hooks.push(hook),
+            Hook::SpanEnter(callback) => self.span_enter_hooks.push(callback),
+            Hook::SpanExit(callback) => self.span_exit_hooks.push(callback),
         }
     }
 
+    /// Returns `true` if no log hook has been set.
+    ///
+    /// Span hooks are not taken into account, as they don't run when writing records.
+    #[cfg(not(feature = "blocking"))]
+    pub fn is_empty(&self) -> bool {
+        self.before_log_hooks.is_empty() && self.after_log_hooks.is_empty()
+    }
+
     /// Triggers all before log hooks
-    /// with the provided log level and target ID.
-    pub fn trigger_before_log(&self, level: LogLevel, target_id: &TargetId) {
-        for hook in &self.before_log_hooks {
-            if let Hook::BeforeLog(callback) = hook {
-                callback(level, target_id);
-            }
-        }
+    /// with the provided record, formatted message and target ID.
+    pub fn trigger_before_log(&self, record: &Record, formatted: &str, target_id: &TargetId) {
+        Self::trigger(&self.before_log_hooks, record, formatted, target_id);
     }
 
     /// Triggers all after log hooks
-    /// with the provided log level and target ID.
-    pub fn trigger_after_log(&self, level: LogLevel, target_id: &TargetId) {
-        for hook in &self.after_log_hooks {
-            if let Hook::AfterLog(callback) = hook {
-                callback(level, target_id);
+    /// with the provided record, formatted message and target ID.
+    pub fn trigger_after_log(&self, record: &Record, formatted: &str, target_id: &TargetId) {
+        Self::trigger(&self.after_log_hooks, record, formatted, target_id);
+    }
+
+    fn trigger(hooks: &[Hook], record: &Record, formatted: &str, target_id: &TargetId) {
+        let _guard = HookGuard::enter();
+
+        for hook in hooks {
+            match hook {
+                Hook::BeforeLog(callback) | Hook::AfterLog(callback) => {
+                    callback(record.level, target_id)
+                }
+                Hook::BeforeLogRecord(callback) | Hook::AfterLogRecord(callback) => {
+                    callback(record, formatted, target_id)
+                }
+                Hook::SpanEnter(_) | Hook::SpanExit(_) => {}
             }
         }
     }
+
+    /// Triggers all span enter hooks with the entered span.
+    pub fn trigger_span_enter(&self, span: &Span) {
+        let _guard = HookGuard::enter();
+
+        for callback in &self.span_enter_hooks {
+            callback(span);
+        }
+    }
+
+    /// Triggers all span exit hooks with the exited span
+    /// and the time elapsed since it was entered.
+    pub fn trigger_span_exit(&self, span: &Span, elapsed: Duration) {
+        let _guard = HookGuard::enter();
+
+        for callback in &self.span_exit_hooks {
+            callback(span, elapsed);
+        }
+    }
+}
+
+thread_local! {
+    /// Whether the current thread is running hooks.
+    static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
+static REENTRANCY_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Marks the current thread as running hooks until dropped.
+struct HookGuard {
+    previous: bool,
+}
+
+impl HookGuard {
+    fn enter() -> Self {
+        Self {
+            previous: IN_HOOK.replace(true),
+        }
+    }
+}
+
+impl Drop for HookGuard {
+    fn drop(&mut self) {
+        IN_HOOK.set(self.previous);
+    }
+}
+
+/// Returns `true` if the record must be dropped because it was logged
+/// from inside a hook, printing a one-time warning to stderr.
+///
+/// Logging from a hook would trigger the hooks again, recursing forever.
+pub(crate) fn reentrant() -> bool {
+    if !IN_HOOK.get() {
+        return false;
+    }
+
+    if !REENTRANCY_WARNED.swap(true, Ordering::Relaxed) {
+        eprintln!(
+            "traccia: a log record was emitted from inside a hook and has been dropped, \
+             as it would trigger the hooks again. Avoid using the logging macros in hooks."
+        );
+    }
+
+    true
+}
+
+/// Runs the span enter hooks, unless the span is entered from inside a hook.
+pub(crate) fn span_entered(span: &Span) {
+    if IN_HOOK.get() {
+        return;
+    }
+
+    if let Ok(hook_system) = HOOK_SYSTEM.read() {
+        hook_system.trigger_span_enter(span);
+    }
+}
+
+/// Runs the span exit hooks, unless the span is exited from inside a hook.
+pub(crate) fn span_exited(span: &Span, elapsed: Duration) {
+    if IN_HOOK.get() {
+        return;
+    }
+
+    if let Ok(hook_system) = HOOK_SYSTEM.read() {
+        hook_system.trigger_span_exit(span, elapsed);
+    }
 }
 
 static HOOK_SYSTEM: LazyLock<RwLock<HookSystem>> = LazyLock::new(|| RwLock::new(HookSystem::new()));
@@ -71,9 +244,9 @@ pub fn hook_system() -> &'static RwLock<HookSystem> {
 ///
 /// Multiple hooks can be set.
 ///
-/// IMPORTANT: do not use the macros defined in this crate in `BeforeLog` or `AfterLog` hooks.
-/// This will lead to an infinite loop, as the logging macros trigger the hooks
-/// themselves.
+/// IMPORTANT: do not use the macros defined in this crate in hooks.
+/// The logging macros trigger the hooks themselves, so this would lead to an infinite loop.
+/// Records logged from a hook are dropped, and a warning is printed to stderr the first time.
 pub fn set_hook(hook: Hook) {
     if let Ok(mut hook_system) = HOOK_SYSTEM.write() {
         hook_system.add_hook(hook);
@@ -81,3 +254,82 @@ pub fn set_hook(hook: Hook) {
         eprintln!("Failed to acquire write lock on hook system. Hook not set.");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reentrancy_guard() {
+        assert!(!reentrant());
+
+        {
+            let _outer = HookGuard::enter();
+            let _inner = HookGuard::enter();
+
+            assert!(reentrant());
+        }
+
+        assert!(!reentrant());
+    }
+
+    #[test]
+    fn test_record_hooks() {
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut hook_system = HookSystem::new();
+
+        let before = Arc::clone(&seen);
+        hook_system.add_hook(Hook::BeforeLogRecord(Box::new(
+            move |record, formatted, _| {
+                before
+                    .lock()
+                    .unwrap()
+                    .push(format!("before {} {}", record.message, formatted));
+            },
+        )));
+
+        let level = Arc::clone(&seen);
+        hook_system.add_hook(Hook::BeforeLog(Box::new(move |record_level, _| {
+            level
+                .lock()
+                .unwrap()
+                .push(format!("level {}", record_level));
+        })));
+
+        let after = Arc::clone(&seen);
+        hook_system.add_hook(Hook::AfterLogRecord(Box::new(move |record, _, target| {
+            after
+                .lock()
+                .unwrap()
+                .push(format!("after {} {:?}", record.message, target));
+        })));
+
+        let record = Record::builder(LogLevel::Error, "disk full").build();
+        let target = TargetId::Custom("test".to_string());
+
+        hook_system.trigger_before_log(&record, "[ERROR] disk full", &target);
+        hook_system.trigger_after_log(&record, "[ERROR] disk full", &target);
+
+        let span = Arc::clone(&seen);
+        hook_system.add_hook(Hook::SpanExit(Box::new(move |exited, elapsed| {
+            span.lock()
+                .unwrap()
+                .push(format!("exit {} {:?}", exited.name, elapsed));
+        })));
+
+        hook_system.trigger_span_enter(&Span::new("request", Vec::new()));
+        hook_system.trigger_span_exit(&Span::new("request", Vec::new()), Duration::from_secs(1));
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "before disk full [ERROR] disk full".to_string(),
+                "level ERROR".to_string(),
+                format!("after disk full {:?}", target),
+                "exit request 1s".to_string(),
+            ]
+        );
+    }
+}
@@ -1,14 +1,42 @@
-use crate::{Config, DefaultFormatter, Formatter, LogLevel, Logger, Record, Target, hooks};
+use crate::{
+    Config, Logger, Record, Target,
+    hooks::{self, HookSystem},
+};
 use std::{
     sync::{Mutex, mpsc},
     thread,
+    time::{Duration, Instant},
 };
 
+/// Name of the worker thread, as shown by debuggers and profilers.
+const WORKER_NAME: &str = "traccia-logger";
+
+/// Maximum number of messages written to the targets in a single batch.
+const MAX_BATCH: usize = 256;
+
+/// Queued record, with its formatted message and the targets selected by the router.
+type Entry = (Record, String, Option<Vec<usize>>);
+
 enum ChannelMessage {
-    Log(String, LogLevel),
+    /// Record to write, along with its formatted message
+    /// and the targets selected by the router, if any.
+    Log(Box<Record>, String, Option<Vec<usize>>),
+    /// Callback to run once all the previous messages are processed
+    /// and the targets flushed.
+    Sync(Box<dyn FnOnce() + Send>),
     Flush,
 }
 
+/// Logger that formats records on the calling thread,
+/// and writes them to the targets from a dedicated worker thread.
+///
+/// A single worker writes to every target, in the order the records are received,
+/// so all the targets see the records in the same global order, even when they are
+/// logged from multiple threads at the same time. Batching preserves this order,
+/// as every target receives the whole batch.
+///
+/// The worker thread is named `traccia-logger`, and its stack size
+/// can be set with [`Config::worker_stack_size`].
 pub struct DefaultLogger {
     config: Config,
     sender: mpsc::Sender<ChannelMessage>,
@@ -20,9 +48,18 @@ impl DefaultLogger {
         let (sender, receiver) = mpsc::channel();
 
         let thread_targerts = config.targets.clone();
-        let worker = std::thread::spawn(move || {
-            Self::worker_thread(receiver, thread_targerts);
-        });
+        let flush_interval = config.flush_interval;
+        let mut builder = thread::Builder::new().name(WORKER_NAME.to_string());
+
+        if let Some(stack_size) = config.worker_stack_size {
+            builder = builder.stack_size(stack_size);
+        }
+
+        let worker = builder
+            .spawn(move || {
+                Self::worker_thread(receiver, thread_targerts, flush_interval);
+            })
+            .expect("Failed to spawn the logger worker thread");
 
         DefaultLogger {
             config,
@@ -31,7 +68,59 @@ impl DefaultLogger {
         }
     }
 
-    fn process_message(formatted: &str, level: LogLevel, targets: &[Box<dyn Target>]) {
+    /// Returns `true` if the record passes the level and the denied modules.
+    fn accepts(&self, record: &Record) -> bool {
+        self.enabled(record.level) && !super::denied(&self.config, record.module_path)
+    }
+
+    /// Formats the record and sends it to the worker thread, along with the formatted message.
+    fn send(&self, record: Record) {
+        let record = super::capture_backtrace(&self.config, &record).unwrap_or(record);
+
+        let formatted = super::format(&self.config, &record);
+
+        let routes = super::route(&self.config, &record);
+
+        let _ = self
+            .sender
+            .send(ChannelMessage::Log(Box::new(record), formatted, routes));
+    }
+
+    fn process_message(
+        (record, formatted, routes): &Entry,
+        targets: &[Box<dyn Target>],
+        hook_system: &HookSystem,
+    ) {
+        for (index, target) in targets.iter().enumerate() {
+            // Check if the level is within the target's level range
+            if !target.accepts(record.level) || !super::routed(routes.as_deref(), index) {
+                continue;
+            }
+
+            let target_id = target.id();
+
+            let message = super::colored_for(target.as_ref(), formatted);
+
+            hook_system.trigger_before_log(record, &message, &target_id);
+
+            if let Err(e) = target.write_record(record, &message) {
+                super::report_write_error(target.as_ref(), e);
+            }
+
+            hook_system.trigger_after_log(record, &message, &target_id);
+        }
+    }
+
+    /// Writes the batched messages to the targets and clears the batch.
+    ///
+    /// When no hooks are set, each target receives all of its messages
+    /// in a single `write_batch` call. Otherwise messages are written one by one,
+    /// so that hooks keep running right before and after each write.
+    fn process_batch(batch: &mut Vec<Entry>, targets: &[Box<dyn Target>]) {
+        if batch.is_empty() {
+            return;
+        }
+
         // Acquire the hook system lock
         // This is a read lock, so it won't block other threads from reading
         // but will block if another thread is writing
@@ -41,77 +130,197 @@ impl DefaultLogger {
             "Failed to acquire the hook system lock. You should use `set_hook` before initializing the logger.",
         );
 
-        for target in targets {
-            // Check if the target has a custom filter level
-            if let Some(filter_level) = target.filter_level()
-                && level < filter_level
-            {
-                continue;
-            }
+        if hook_system.is_empty() {
+            for (index, target) in targets.iter().enumerate() {
+                let messages = batch
+                    .iter()
+                    .filter(|(record, _, routes)| {
+                        target.accepts(record.level) && super::routed(routes.as_deref(), index)
+                    })
+                    .map(|(record, formatted, _)| {
+                        (record, super::colored_for(target.as_ref(), formatted))
+                    })
+                    .collect::<Vec<_>>();
 
-            let target_id = target.id();
+                let entries = messages
+                    .iter()
+                    .map(|(record, formatted)| (*record, formatted.as_ref()))
+                    .collect::<Vec<_>>();
 
-            hook_system.trigger_before_log(level, &target_id);
+                if entries.is_empty() {
+                    continue;
+                }
 
-            if let Err(e) = target.write(level, formatted) {
-                eprintln!("Failed to write to target: {}", e);
+                if let Err(e) = target.write_batch(&entries) {
+                    super::report_write_error(target.as_ref(), e);
+                }
             }
+        } else {
+            for entry in batch.iter() {
+                Self::process_message(entry, targets, &hook_system);
+            }
+        }
 
-            hook_system.trigger_after_log(level, &target_id);
+        batch.clear();
+    }
+
+    /// Flushes every target, returning the time of the flush.
+    fn flush_targets(targets: &[Box<dyn Target>]) -> Instant {
+        for target in targets {
+            if let Err(e) = target.flush() {
+                eprintln!("Failed to flush target {}: {}", target.id(), e);
+            }
         }
+
+        Instant::now()
     }
 
-    fn worker_thread(receiver: mpsc::Receiver<ChannelMessage>, targets: Vec<Box<dyn Target>>) {
-        loop {
-            match receiver.recv() {
-                Ok(ChannelMessage::Log(formatted, level)) => {
-                    Self::process_message(&formatted, level, &targets)
+    fn worker_thread(
+        receiver: mpsc::Receiver<ChannelMessage>,
+        targets: Vec<Box<dyn Target>>,
+        flush_interval: Option<Duration>,
+    ) {
+        let mut batch = Vec::with_capacity(MAX_BATCH);
+        let mut running = true;
+
+        // Whether records were written since the targets were last flushed
+        let mut unflushed = false;
+        let mut last_flush = Instant::now();
+
+        while running {
+            // Only wake up on a timeout when there is something to flush
+            let mut next = match flush_interval.filter(|_| unflushed) {
+                Some(interval) => {
+                    match receiver.recv_timeout(interval.saturating_sub(last_flush.elapsed())) {
+                        Ok(message) => Some(message),
+                        Err(mpsc::RecvTimeoutError::Timeout) => {
+                            last_flush = Self::flush_targets(&targets);
+                            unflushed = false;
+                            continue;
+                        }
+                        Err(mpsc::RecvTimeoutError::Disconnected) => None,
+                    }
+                }
+                None => receiver.recv().ok(),
+            };
+
+            if next.is_none() {
+                break;
+            }
+
+            // Collect the messages that are already queued
+            while let Some(message) = next.take() {
+                match message {
+                    ChannelMessage::Log(record, formatted, routes) => {
+                        batch.push((*record, formatted, routes));
+                        unflushed = true;
+                    }
+
+                    ChannelMessage::Sync(done) => {
+                        Self::process_batch(&mut batch, &targets);
+                        last_flush = Self::flush_targets(&targets);
+                        unflushed = false;
+                        done();
+                    }
+
+                    ChannelMessage::Flush => {
+                        running = false;
+                        break;
+                    }
                 }
 
-                Ok(ChannelMessage::Flush) => break,
+                if batch.len() >= MAX_BATCH {
+                    Self::process_batch(&mut batch, &targets);
+                }
 
-                Err(_) => break,
+                next = receiver.try_recv().ok();
+            }
+
+            Self::process_batch(&mut batch, &targets);
+
+            // Keep flushing under a steady load, which never lets the timeout expire
+            if unflushed && flush_interval.is_some_and(|interval| last_flush.elapsed() >= interval)
+            {
+                last_flush = Self::flush_targets(&targets);
+                unflushed = false;
             }
         }
 
         // Drain the remaining messages
         while let Ok(message) = receiver.try_recv() {
-            if let ChannelMessage::Log(formatted, level) = message {
-                Self::process_message(&formatted, level, &targets)
+            match message {
+                ChannelMessage::Log(record, formatted, routes) => {
+                    batch.push((*record, formatted, routes))
+                }
+
+                ChannelMessage::Sync(done) => {
+                    Self::process_batch(&mut batch, &targets);
+                    Self::flush_targets(&targets);
+                    done();
+                }
+
+                ChannelMessage::Flush => {}
             }
         }
+
+        Self::process_batch(&mut batch, &targets);
+        Self::flush_targets(&targets);
     }
 }
 
 impl Logger for DefaultLogger {
     fn enabled(&self, level: crate::LogLevel) -> bool {
-        self.config.level <= level
+        crate::thread_level()
+            .unwrap_or(self.config.level)
+            .includes(level)
     }
 
     fn abort(&self) {
         let _ = self.sender.send(ChannelMessage::Flush);
         if let Ok(mut handle) = self.worker.lock() {
-            if let Some(handle) = handle.take() {
-                handle.join().unwrap();
+            // Called from `Drop` too, so a panic of the worker is reported instead of propagated
+            if let Some(handle) = handle.take()
+                && handle.join().is_err()
+            {
+                eprintln!("The logger worker thread panicked. Some final logs may not be written.");
             }
         } else {
             eprintln!("Cleanup process failed. Some final logs may not be written.");
         }
     }
 
+    fn flush(&self, done: Box<dyn FnOnce() + Send>) {
+        // If the worker has already stopped, there is nothing left to wait for
+        if let Err(mpsc::SendError(ChannelMessage::Sync(done))) =
+            self.sender.send(ChannelMessage::Sync(done))
+        {
+            done();
+        }
+    }
+
+    fn config(&self) -> Option<&Config> {
+        Some(&self.config)
+    }
+
     fn log(&self, record: &Record) {
-        if !self.enabled(record.level) {
-            return;
+        if self.accepts(record) {
+            self.send(record.clone());
         }
+    }
 
-        let formatted = match &self.config.format {
-            Some(formatter) => formatter.format(record),
-            None => DefaultFormatter::new().format(record),
-        };
+    /// Moves the record to the worker thread, without copying it.
+    fn log_owned(&self, record: Record) {
+        if self.accepts(&record) {
+            self.send(record);
+        }
+    }
+}
 
-        let _ = self
-            .sender
-            .send(ChannelMessage::Log(formatted, record.level));
+impl Drop for DefaultLogger {
+    /// Writes the pending records and stops the worker thread,
+    /// unless [`Logger::abort`] was already called.
+    fn drop(&mut self) {
+        self.abort();
     }
 }
 
@@ -120,3 +329,294 @@ impl Default for DefaultLogger {
         DefaultLogger::new(Config::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, LogLevel, Record};
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    impl Target for Capture {
+        fn write(&self, _: LogLevel, formatted: &str) -> Result<(), Error> {
+            self.0.lock().unwrap().push(formatted.to_string());
+            Ok(())
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Flushes(Arc<Mutex<usize>>);
+
+    impl Target for Flushes {
+        fn write(&self, _: LogLevel, _: &str) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn flush(&self) -> Result<(), Error> {
+            *self.0.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Plain(Capture);
+
+    impl Target for Plain {
+        fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+            self.0.write(level, formatted)
+        }
+
+        fn wants_color(&self) -> bool {
+            false
+        }
+    }
+
+    struct Failing;
+
+    impl crate::Formatter for Failing {
+        fn format(&self, _: &Record) -> String {
+            unreachable!()
+        }
+
+        fn try_format(&self, _: &Record) -> Result<String, Error> {
+            Err(Error::Format("unsupported value".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_failed_format_falls_back_to_default() {
+        let capture = Capture::default();
+        let logger = DefaultLogger::new(Config {
+            targets: vec![Box::new(capture.clone())],
+            format: Some(Box::new(Failing)),
+            ..Config::default()
+        });
+
+        logger.log(&Record::builder(LogLevel::Info, "kept").build());
+
+        let (sender, receiver) = mpsc::channel();
+        logger.flush(Box::new(move || sender.send(()).unwrap()));
+        receiver.recv().unwrap();
+
+        assert_eq!(
+            crate::util::strip_ansi_codes(&capture.0.lock().unwrap()[0]),
+            "[INFO] kept"
+        );
+
+        logger.abort();
+    }
+
+    #[test]
+    fn test_flush_waits_for_pending_records() {
+        let capture = Capture::default();
+        let logger = DefaultLogger::new(Config {
+            targets: vec![Box::new(capture.clone())],
+            ..Config::default()
+        });
+
+        for i in 0..100 {
+            logger.log(&Record::builder(LogLevel::Info, i.to_string()).build());
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        logger.flush(Box::new(move || sender.send(()).unwrap()));
+        receiver.recv().unwrap();
+
+        assert_eq!(capture.0.lock().unwrap().len(), 100);
+
+        logger.abort();
+    }
+
+    #[test]
+    fn test_colors_stripped_for_plain_targets() {
+        let colored = Capture::default();
+        let plain = Plain::default();
+        let logger = DefaultLogger::new(Config {
+            targets: vec![Box::new(colored.clone()), Box::new(plain.clone())],
+            ..Config::default()
+        });
+
+        logger.log(&Record::builder(LogLevel::Info, "\x1b[31mred\x1b[39m").build());
+        logger.abort();
+
+        assert!(colored.0.lock().unwrap()[0].ends_with("\x1b[31mred\x1b[39m"));
+        assert_eq!(*plain.0.0.lock().unwrap(), ["[INFO] red"]);
+    }
+
+    #[test]
+    fn test_denied_modules() {
+        let capture = Capture::default();
+        let logger = DefaultLogger::new(
+            Config {
+                targets: vec![Box::new(capture.clone())],
+                ..Config::default()
+            }
+            .with_denied_module("mio"),
+        );
+
+        for module_path in ["mio", "mio::poll", "miova", "app"] {
+            logger.log(
+                &Record::builder(LogLevel::Info, module_path)
+                    .module_path(module_path)
+                    .build(),
+            );
+        }
+
+        // Denied records are not even built
+        crate::__log_to(&logger, LogLevel::Info, "mio::poll", || unreachable!());
+
+        logger.abort();
+
+        let written = capture.0.lock().unwrap();
+
+        assert_eq!(written.len(), 2);
+        assert!(written[0].ends_with("miova") && written[1].ends_with("app"));
+    }
+
+    #[test]
+    fn test_router_selects_targets() {
+        let console = Capture::default();
+        let audit = Capture::default();
+        let logger = DefaultLogger::new(Config {
+            targets: vec![Box::new(console.clone()), Box::new(audit.clone())],
+            router: Some(Box::new(|record| {
+                if record.message.contains("PAYMENT") {
+                    vec![1, 7]
+                } else {
+                    vec![0]
+                }
+            })),
+            ..Config::default()
+        });
+
+        logger.log(&Record::builder(LogLevel::Info, "started").build());
+        logger.log(&Record::builder(LogLevel::Info, "PAYMENT 42").build());
+        logger.abort();
+
+        let console = console.0.lock().unwrap();
+        let audit = audit.0.lock().unwrap();
+
+        assert!(console.len() == 1 && console[0].ends_with("started"));
+        assert!(audit.len() == 1 && audit[0].ends_with("PAYMENT 42"));
+    }
+
+    #[test]
+    fn test_targets_share_global_order() {
+        let console = Capture::default();
+        let file = Capture::default();
+        let logger = DefaultLogger::new(Config {
+            targets: vec![Box::new(console.clone()), Box::new(file.clone())],
+            ..Config::default()
+        });
+
+        thread::scope(|scope| {
+            for t in 0..4 {
+                let logger = &logger;
+
+                scope.spawn(move || {
+                    for i in 0..500 {
+                        logger.log(&Record::builder(LogLevel::Info, format!("{t}-{i}")).build());
+                    }
+                });
+            }
+        });
+
+        let (sender, receiver) = mpsc::channel();
+        logger.flush(Box::new(move || sender.send(()).unwrap()));
+        receiver.recv().unwrap();
+
+        let console = console.0.lock().unwrap();
+
+        assert_eq!(console.len(), 2000);
+        assert_eq!(*console, *file.0.lock().unwrap());
+
+        logger.abort();
+    }
+
+    #[test]
+    fn test_drop_writes_pending_records() {
+        let capture = Capture::default();
+        let logger = DefaultLogger::new(Config {
+            targets: vec![Box::new(capture.clone())],
+            ..Config::default()
+        });
+
+        for i in 0..100 {
+            logger.log(&Record::builder(LogLevel::Info, i.to_string()).build());
+        }
+
+        drop(logger);
+
+        assert_eq!(capture.0.lock().unwrap().len(), 100);
+    }
+
+    #[derive(Clone)]
+    struct Panicking;
+
+    impl Target for Panicking {
+        fn write(&self, _: LogLevel, _: &str) -> Result<(), Error> {
+            panic!("target failed");
+        }
+    }
+
+    #[test]
+    fn test_drop_after_worker_panic() {
+        let logger = DefaultLogger::new(Config {
+            targets: vec![Box::new(Panicking)],
+            ..Config::default()
+        });
+
+        logger.log(&Record::builder(LogLevel::Info, "boom").build());
+
+        // Doesn't propagate the panic of the worker
+        drop(logger);
+    }
+
+    #[derive(Clone, Default)]
+    struct ThreadName(Arc<Mutex<Option<String>>>);
+
+    impl Target for ThreadName {
+        fn write(&self, _: LogLevel, _: &str) -> Result<(), Error> {
+            *self.0.lock().unwrap() = thread::current().name().map(str::to_string);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_worker_thread_named() {
+        let name = ThreadName::default();
+        let logger = DefaultLogger::new(Config {
+            targets: vec![Box::new(name.clone())],
+            worker_stack_size: Some(256 * 1024),
+            ..Config::default()
+        });
+
+        logger.log(&Record::builder(LogLevel::Info, "named").build());
+        logger.abort();
+
+        assert_eq!(name.0.lock().unwrap().as_deref(), Some(WORKER_NAME));
+    }
+
+    #[test]
+    fn test_periodic_flush_when_idle() {
+        let flushes = Flushes::default();
+        let logger = DefaultLogger::new(Config {
+            targets: vec![Box::new(flushes.clone())],
+            flush_interval: Some(Duration::from_millis(10)),
+            ..Config::default()
+        });
+
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(*flushes.0.lock().unwrap(), 0);
+
+        logger.log(&Record::builder(LogLevel::Info, "buffered").build());
+        thread::sleep(Duration::from_millis(100));
+
+        // Flushed once after the write, then nothing left to flush
+        assert_eq!(*flushes.0.lock().unwrap(), 1);
+
+        logger.abort();
+    }
+}
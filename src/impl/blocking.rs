@@ -1,4 +1,4 @@
-use crate::{Config, DefaultFormatter, Formatter, Logger, Record, hooks};
+use crate::{Config, Logger, Record, hooks};
 
 #[derive(Default)]
 pub struct DefaultLogger {
@@ -13,18 +13,35 @@ impl DefaultLogger {
 
 impl Logger for DefaultLogger {
     fn enabled(&self, level: crate::LogLevel) -> bool {
-        self.config.level <= level
+        crate::thread_level()
+            .unwrap_or(self.config.level)
+            .includes(level)
+    }
+
+    fn config(&self) -> Option<&Config> {
+        Some(&self.config)
+    }
+
+    /// Flushes the targets, as records are already written.
+    fn flush(&self, done: Box<dyn FnOnce() + Send>) {
+        for target in &self.config.targets {
+            if let Err(e) = target.flush() {
+                eprintln!("Failed to flush target {}: {}", target.id(), e);
+            }
+        }
+
+        done();
     }
 
     fn log(&self, record: &Record) {
-        if !self.enabled(record.level) {
+        if !self.enabled(record.level) || super::denied(&self.config, record.module_path) {
             return;
         }
 
-        let formatted = match &self.config.format {
-            Some(formatter) => formatter.format(record),
-            None => DefaultFormatter::new().format(record),
-        };
+        let captured = super::capture_backtrace(&self.config, record);
+        let record = captured.as_ref().unwrap_or(record);
+
+        let formatted = super::format(&self.config, record);
 
         // Acquire the hook system lock
         // This is a read lock, so it won't block other threads from reading
@@ -35,23 +52,25 @@ impl Logger for DefaultLogger {
             "Failed to acquire the hook system lock. You should use `set_hook` before initializing the logger.",
         );
 
-        for target in &self.config.targets {
-            // Check if the target has a custom filter level
-            if let Some(filter_level) = target.filter_level()
-                && record.level < filter_level
-            {
+        let routes = super::route(&self.config, record);
+
+        for (index, target) in self.config.targets.iter().enumerate() {
+            // Check if the level is within the target's level range
+            if !target.accepts(record.level) || !super::routed(routes.as_deref(), index) {
                 continue;
             }
 
             let target_id = target.id();
 
-            hook_system.trigger_before_log(record.level, &target_id);
+            let message = super::colored_for(target.as_ref(), &formatted);
+
+            hook_system.trigger_before_log(record, &message, &target_id);
 
-            if let Err(e) = target.write(record.level, &formatted) {
-                eprintln!("Failed to write to target: {}", e);
+            if let Err(e) = target.write_record(record, &message) {
+                super::report_write_error(target.as_ref(), e);
             }
 
-            hook_system.trigger_after_log(record.level, &target_id);
+            hook_system.trigger_after_log(record, &message, &target_id);
         }
     }
 }
@@ -3,3 +3,81 @@ pub mod r#async;
 
 #[cfg(feature = "blocking")]
 pub mod blocking;
+
+pub mod null;
+
+use crate::{Backtrace, Config, Error, Formatter, Record, Target, format::DEFAULT_FORMATTER, util};
+use std::borrow::Cow;
+
+/// Returns a copy of the record with a backtrace attached,
+/// if the config asks for one at the record level.
+///
+/// Returns `None` when no backtrace is needed or when backtraces
+/// are disabled by the environment.
+pub(crate) fn capture_backtrace(config: &Config, record: &Record) -> Option<Record> {
+    let threshold = config.capture_backtrace_at?;
+
+    if record.level < threshold || record.backtrace.is_some() {
+        return None;
+    }
+
+    Some(Record {
+        backtrace: Some(Backtrace::capture()?),
+        ..record.clone()
+    })
+}
+
+/// Returns `true` if the module is in `Config::denied_modules`.
+pub(crate) fn denied(config: &Config, module_path: Option<&str>) -> bool {
+    let Some(module_path) = module_path else {
+        return false;
+    };
+
+    config.denied_modules.iter().any(|denied| {
+        module_path
+            .strip_prefix(denied.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    })
+}
+
+/// Returns the indices of the targets selected by the configured router
+/// for the record, or `None` if no router is set.
+pub(crate) fn route(config: &Config, record: &Record) -> Option<Vec<usize>> {
+    config.router.as_ref().map(|router| router(record))
+}
+
+/// Returns `true` if the target at `index` is selected by the routes.
+pub(crate) fn routed(routes: Option<&[usize]>, index: usize) -> bool {
+    routes.is_none_or(|routes| routes.contains(&index))
+}
+
+/// Returns the formatted message to write to the target,
+/// without the colors if the target doesn't want them.
+pub(crate) fn colored_for<'a>(target: &dyn Target, formatted: &'a str) -> Cow<'a, str> {
+    if target.wants_color() {
+        Cow::Borrowed(formatted)
+    } else {
+        util::strip_ansi_codes_cow(formatted)
+    }
+}
+
+/// Prints an error returned by a target to stderr, along with the id of the target.
+pub(crate) fn report_write_error(target: &dyn Target, e: Error) {
+    let e = Error::TargetWrite {
+        id: target.id(),
+        source: Box::new(e),
+    };
+
+    eprintln!("{}", crate::error_chain(&e));
+}
+
+/// Formats a record with the configured formatter.
+///
+/// If the formatter fails, the error is printed to stderr
+/// and the record is formatted with the default formatter.
+pub(crate) fn format(config: &Config, record: &Record) -> String {
+    config.formatter().try_format(record).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        DEFAULT_FORMATTER.format(record)
+    })
+}
@@ -0,0 +1,27 @@
+use crate::{LogLevel, Logger, Record};
+
+/// Logger that discards every record.
+///
+/// It has no worker thread and no targets, and reports every level as disabled.
+/// Useful to silence logging entirely, or as a baseline when measuring logging overhead.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// traccia::init_null();
+///
+/// traccia::error!("Never written anywhere");
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullLogger;
+
+impl Logger for NullLogger {
+    fn enabled(&self, _: LogLevel) -> bool {
+        false
+    }
+
+    fn log(&self, _: &Record) {}
+
+    #[cfg(not(feature = "blocking"))]
+    fn abort(&self) {}
+}
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{cmp::Ordering, str::FromStr};
 
 /// Log level definitions and utilities.
 use crate::{Color, Colorize};
@@ -11,7 +11,9 @@ use crate::{Color, Colorize};
 /// - `Info`: General information about application progress
 /// - `Warn`: Potentially harmful situations that might need attention
 /// - `Error`: Error events that might still allow the application to continue running
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///
+/// Domain specific levels can be defined with [`LogLevel::custom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LogLevel {
     /// Very detailed information for debugging specific issues
     Trace,
@@ -25,6 +27,19 @@ pub enum LogLevel {
     Error,
     /// Fatal error events that lead to application termination
     Fatal,
+    /// User defined level.
+    ///
+    /// The severity uses the same scale as the built-in levels
+    /// (`Trace` = 0 up to `Fatal` = 5). A custom level ranks right above
+    /// the built-in level with the same severity, so a `NOTICE` level with
+    /// severity 2 sits between `Info` and `Warn`.
+    /// Severities above 5 rank above `Fatal`.
+    Custom {
+        /// The name printed in place of the level, e.g. `NOTICE`
+        name: &'static str,
+        /// The severity used for ordering and filtering
+        severity: u8,
+    },
 }
 
 impl std::fmt::Display for LogLevel {
@@ -41,35 +56,169 @@ impl std::fmt::Display for LogLevel {
             LogLevel::Warn => write!(f, "WARN"),
             LogLevel::Error => write!(f, "ERROR"),
             LogLevel::Fatal => write!(f, "FATAL"),
+            LogLevel::Custom { name, .. } => write!(f, "{}", name),
         }
     }
 }
 
 impl LogLevel {
-    /// Returns the level string with appropriate color formatting.
+    /// Creates a custom log level with the given name and severity.
+    ///
+    /// Being a `const fn`, custom levels can be declared as constants
+    /// and used with the `log!` macro like any other level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::LogLevel;
+    ///
+    /// const NOTICE: LogLevel = LogLevel::custom("NOTICE", 2);
+    ///
+    /// assert!(LogLevel::Info < NOTICE);
+    /// assert!(NOTICE < LogLevel::Warn);
+    /// assert_eq!(NOTICE.to_string(), "NOTICE");
+    /// ```
+    pub const fn custom(name: &'static str, severity: u8) -> Self {
+        LogLevel::Custom { name, severity }
+    }
+
+    /// Returns the severity of the level.
+    ///
+    /// Built-in levels go from `Trace` (0) to `Fatal` (5),
+    /// custom levels return the severity they were created with.
+    pub const fn severity(&self) -> u8 {
+        match self {
+            LogLevel::Trace => 0,
+            LogLevel::Debug => 1,
+            LogLevel::Info => 2,
+            LogLevel::Warn => 3,
+            LogLevel::Error => 4,
+            LogLevel::Fatal => 5,
+            LogLevel::Custom { severity, .. } => *severity,
+        }
+    }
+
+    /// Returns the numeric code of the level, as used by the `u8` conversions.
+    ///
+    /// This is the same as [`severity`](LogLevel::severity): built-in levels go from
+    /// `Trace` (0) to `Fatal` (5) and round-trip through [`LogLevel::try_from`],
+    /// custom levels map to their severity, losing their name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::LogLevel;
+    ///
+    /// assert_eq!(LogLevel::Warn.as_u8(), 3);
+    /// assert_eq!(LogLevel::try_from(LogLevel::Warn.as_u8()).unwrap(), LogLevel::Warn);
+    /// ```
+    pub const fn as_u8(&self) -> u8 {
+        self.severity()
+    }
+
+    /// Returns `true` if a record at `level` should be shown when `self`
+    /// is the minimum level, i.e. if `level` is at least as severe as `self`.
+    ///
+    /// This is the check to use in [`Logger::enabled`](crate::Logger::enabled),
+    /// and is equivalent to `self <= level`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::LogLevel;
+    ///
+    /// let minimum = LogLevel::Info;
+    ///
+    /// assert!(minimum.includes(LogLevel::Error));
+    /// assert!(minimum.includes(LogLevel::Info));
+    /// assert!(!minimum.includes(LogLevel::Debug));
+    /// ```
+    pub fn includes(&self, level: LogLevel) -> bool {
+        *self <= level
+    }
+
+    /// Returns the color associated with the level.
     ///
-    /// Each log level has an associated color:
     /// - Trace: Cyan
     /// - Debug: Blue
     /// - Info: Green
     /// - Warn: Yellow
     /// - Error: Red
+    /// - Fatal: Bright red
+    /// - Custom levels: Magenta
+    pub fn default_color(&self) -> Color {
+        match self {
+            LogLevel::Trace => Color::Cyan,
+            LogLevel::Debug => Color::Blue,
+            LogLevel::Info => Color::Green,
+            LogLevel::Warn => Color::Yellow,
+            LogLevel::Error => Color::Red,
+            LogLevel::Fatal => Color::BrightRed,
+            LogLevel::Custom { .. } => Color::Magenta,
+        }
+    }
+
+    /// Returns the level string with appropriate color formatting.
+    ///
+    /// Each log level is colored with its [`default_color`](LogLevel::default_color).
+    /// Without the `color` feature, the plain level string is returned.
     ///
     /// # Returns
     ///
     /// The formatted string with ANSI color codes applied
     pub fn default_coloring(&self) -> String {
-        match self {
-            LogLevel::Trace => format!("{}", self).color(Color::Cyan),
-            LogLevel::Debug => format!("{}", self).color(Color::Blue),
-            LogLevel::Info => format!("{}", self).color(Color::Green),
-            LogLevel::Warn => format!("{}", self).color(Color::Yellow),
-            LogLevel::Error => format!("{}", self).color(Color::Red),
-            LogLevel::Fatal => format!("{}", self).color(Color::BrightRed),
+        format!("{}", self).color(self.default_color())
+    }
+
+    /// Returns the level string without colors, the same as `to_string`.
+    ///
+    /// The uncolored counterpart of [`default_coloring`](LogLevel::default_coloring).
+    pub fn plain(&self) -> String {
+        self.to_string()
+    }
+
+    /// Returns the level string, colored with its default color only if `colored` is set.
+    ///
+    /// Useful in formatters that color their output conditionally.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::LogLevel;
+    ///
+    /// assert_eq!(LogLevel::Warn.coloring(false), "WARN");
+    /// assert_eq!(LogLevel::Warn.coloring(true), LogLevel::Warn.default_coloring());
+    /// ```
+    pub fn coloring(&self, colored: bool) -> String {
+        if colored {
+            self.default_coloring()
+        } else {
+            self.plain()
         }
     }
 }
 
+/// Levels are ordered by severity.
+///
+/// A custom level ranks right after the built-in level with the same severity,
+/// custom levels sharing a severity are ordered by name.
+impl Ord for LogLevel {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |level: &LogLevel| match level {
+            LogLevel::Custom { name, severity } => (*severity, 1, *name),
+            _ => (level.severity(), 0, ""),
+        };
+
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for LogLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// The default log level is info
 impl Default for LogLevel {
     fn default() -> Self {
@@ -81,22 +230,35 @@ impl Default for LogLevel {
 ///
 /// Useful for things like clap to parse the log level via
 /// command-line arguments.
+///
+/// Matching is case-insensitive, and common aliases are accepted:
+/// `verbose` for `Trace`, `warning` for `Warn`, `err` for `Error`,
+/// `critical` and `crit` for `Fatal`.
 impl FromStr for LogLevel {
     type Err = crate::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
-            "trace" => Ok(LogLevel::Trace),
+            "trace" | "verbose" => Ok(LogLevel::Trace),
             "debug" => Ok(LogLevel::Debug),
             "info" => Ok(LogLevel::Info),
-            "warn" => Ok(LogLevel::Warn),
-            "error" => Ok(LogLevel::Error),
-            "fatal" => Ok(LogLevel::Fatal),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" | "err" => Ok(LogLevel::Error),
+            "fatal" | "critical" | "crit" => Ok(LogLevel::Fatal),
             _ => Err(crate::Error::ParseLogLevel),
         }
     }
 }
 
+/// Parse the log level from &str, same as [`FromStr`].
+impl TryFrom<&str> for LogLevel {
+    type Error = crate::Error;
+
+    fn try_from(value: &str) -> Result<Self, crate::Error> {
+        value.parse()
+    }
+}
+
 /// Tryfrom u8 parsing implementation
 impl TryFrom<u8> for LogLevel {
     type Error = crate::Error;
@@ -114,18 +276,46 @@ impl TryFrom<u8> for LogLevel {
     }
 }
 
-/// TryFrom log level implementation for u8
-impl TryFrom<LogLevel> for u8 {
-    type Error = crate::Error;
+/// From log level implementation for u8, see [`LogLevel::as_u8`].
+///
+/// Custom levels are converted to their severity.
+impl From<LogLevel> for u8 {
+    fn from(value: LogLevel) -> Self {
+        value.as_u8()
+    }
+}
 
-    fn try_from(value: LogLevel) -> Result<Self, Self::Error> {
-        match value {
-            LogLevel::Trace => Ok(0),
-            LogLevel::Debug => Ok(1),
-            LogLevel::Info => Ok(2),
-            LogLevel::Warn => Ok(3),
-            LogLevel::Error => Ok(4),
-            LogLevel::Fatal => Ok(5),
+/// Converts a level of the `log` crate, when the "log" feature is enabled.
+///
+/// Each level maps to the traccia level with the same name.
+/// `log` has no `Fatal` level, so converted levels are never `Fatal`:
+/// fatal errors reported through `log` end up as `Error`.
+#[cfg(feature = "log")]
+impl From<log::Level> for LogLevel {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Trace => LogLevel::Trace,
+            log::Level::Debug => LogLevel::Debug,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Warn => LogLevel::Warn,
+            log::Level::Error => LogLevel::Error,
+        }
+    }
+}
+
+/// Converts a level of the `tracing` crate, when the "tracing" feature is enabled.
+///
+/// Each level maps to the traccia level with the same name.
+/// As with `log`, there is no `Fatal` level in `tracing`, so converted levels are never `Fatal`.
+#[cfg(feature = "tracing")]
+impl From<tracing_core::Level> for LogLevel {
+    fn from(level: tracing_core::Level) -> Self {
+        match level {
+            tracing_core::Level::TRACE => LogLevel::Trace,
+            tracing_core::Level::DEBUG => LogLevel::Debug,
+            tracing_core::Level::INFO => LogLevel::Info,
+            tracing_core::Level::WARN => LogLevel::Warn,
+            _ => LogLevel::Error,
         }
     }
 }
@@ -154,6 +344,7 @@ impl clap::ValueEnum for LogLevel {
     /// Returns an array slice containing all variants of LogLevel.
     ///
     /// This is used by clap to validate and parse command-line arguments.
+    /// Custom levels are not listed, as they can't be known in advance.
     fn value_variants<'a>() -> &'a [Self] {
         &[
             LogLevel::Trace,
@@ -172,12 +363,16 @@ impl clap::ValueEnum for LogLevel {
     /// - An uppercase alias (e.g., "DEBUG")
     /// - A title case alias (e.g., "Debug")
     ///
+    /// The same applies to the aliases accepted by [`FromStr`],
+    /// e.g. "warning", "WARNING" and "Warning" for `Warn`.
+    ///
     /// This allows users to specify the log level in any case they prefer.
     fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
         Some(match self {
             LogLevel::Trace => clap::builder::PossibleValue::new("trace")
                 .alias("TRACE")
-                .alias("Trace"),
+                .alias("Trace")
+                .aliases(["verbose", "VERBOSE", "Verbose"]),
             LogLevel::Debug => clap::builder::PossibleValue::new("debug")
                 .alias("DEBUG")
                 .alias("Debug"),
@@ -186,13 +381,110 @@ impl clap::ValueEnum for LogLevel {
                 .alias("Info"),
             LogLevel::Warn => clap::builder::PossibleValue::new("warn")
                 .alias("WARN")
-                .alias("Warn"),
+                .alias("Warn")
+                .aliases(["warning", "WARNING", "Warning"]),
             LogLevel::Error => clap::builder::PossibleValue::new("error")
                 .alias("ERROR")
-                .alias("Error"),
+                .alias("Error")
+                .aliases(["err", "ERR", "Err"]),
             LogLevel::Fatal => clap::builder::PossibleValue::new("fatal")
                 .alias("FATAL")
-                .alias("Fatal"),
+                .alias("Fatal")
+                .aliases(["critical", "CRITICAL", "Critical", "crit", "CRIT", "Crit"]),
+            LogLevel::Custom { name, .. } => clap::builder::PossibleValue::new(*name),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTICE: LogLevel = LogLevel::custom("NOTICE", 2);
+    const AUDIT: LogLevel = LogLevel::custom("AUDIT", 10);
+
+    #[test]
+    fn test_custom_level_ordering() {
+        assert!(LogLevel::Info < NOTICE);
+        assert!(NOTICE < LogLevel::Warn);
+        assert!(LogLevel::Fatal < AUDIT);
+        assert!(LogLevel::custom("A", 2) < LogLevel::custom("B", 2));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let levels = [
+            LogLevel::Trace,
+            LogLevel::Debug,
+            LogLevel::Info,
+            LogLevel::Warn,
+            LogLevel::Error,
+            LogLevel::Fatal,
+        ];
+
+        assert!(levels.windows(2).all(|pair| pair[0] < pair[1]));
+
+        for (i, minimum) in levels.iter().enumerate() {
+            for (j, level) in levels.iter().enumerate() {
+                assert_eq!(minimum.includes(*level), i <= j);
+            }
+        }
+
+        assert!(LogLevel::Info.includes(NOTICE));
+        assert!(!LogLevel::Warn.includes(NOTICE));
+    }
+
+    #[test]
+    fn test_custom_level_display() {
+        assert_eq!(NOTICE.to_string(), "NOTICE");
+        assert_eq!(NOTICE.plain(), "NOTICE");
+        assert_eq!(NOTICE.severity(), 2);
+        assert_eq!(u8::from(NOTICE), 2);
+    }
+
+    #[test]
+    fn test_u8_round_trip() {
+        for code in 0..=5 {
+            assert_eq!(u8::from(LogLevel::try_from(code).unwrap()), code);
+        }
+
+        assert!(LogLevel::try_from(6).is_err());
+    }
+
+    #[test]
+    fn test_parse_aliases() {
+        assert_eq!("WARNING".parse::<LogLevel>().unwrap(), LogLevel::Warn);
+        assert_eq!(LogLevel::try_from("err").unwrap(), LogLevel::Error);
+        assert_eq!("Crit".parse::<LogLevel>().unwrap(), LogLevel::Fatal);
+        assert_eq!("critical".parse::<LogLevel>().unwrap(), LogLevel::Fatal);
+        assert_eq!("verbose".parse::<LogLevel>().unwrap(), LogLevel::Trace);
+        assert!("warnings".parse::<LogLevel>().is_err());
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_from_log_level() {
+        assert_eq!(LogLevel::from(log::Level::Trace), LogLevel::Trace);
+        assert_eq!(LogLevel::from(log::Level::Warn), LogLevel::Warn);
+        assert_eq!(LogLevel::from(log::Level::Error), LogLevel::Error);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_from_tracing_level() {
+        assert_eq!(LogLevel::from(tracing_core::Level::TRACE), LogLevel::Trace);
+        assert_eq!(LogLevel::from(tracing_core::Level::INFO), LogLevel::Info);
+        assert_eq!(LogLevel::from(tracing_core::Level::ERROR), LogLevel::Error);
+    }
+
+    #[cfg(feature = "clap")]
+    #[test]
+    fn test_clap_aliases() {
+        use clap::ValueEnum;
+
+        let parse = |s| <LogLevel as ValueEnum>::from_str(s, false).unwrap();
+
+        assert_eq!(parse("Warning"), LogLevel::Warn);
+        assert_eq!(parse("crit"), LogLevel::Fatal);
+    }
+}
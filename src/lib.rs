@@ -24,70 +24,105 @@
 //! debug!("This won't be displayed with Info level");
 //! error!("Something went wrong: {}", error);
 //! ```
+mod banner;
+mod clock;
+mod counts;
+mod early;
 mod error;
+mod ext;
+mod flush;
 mod format;
 mod hooks;
 mod r#impl;
 mod level;
 mod macros;
+mod record;
+mod shutdown;
 mod span;
 mod strings;
+mod suppress;
 mod target;
+mod tee;
+mod thread_level;
+mod timer;
+mod trace_id;
+mod unique;
 mod util;
 
-#[cfg(not(feature = "blocking"))]
-mod shutdown;
-
-use std::{sync::OnceLock, thread::ThreadId};
+use std::{sync::OnceLock, time::Duration};
 
 // Exports
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use counts::{counts, suppressed_count};
+pub use early::{buffer_early_logs, warn_if_uninitialized};
 pub use error::Error;
+pub use ext::{OptionLogExt, error_chain, hex_dump};
+pub use flush::{Flush, flush, flush_async};
 pub use format::{
-    DefaultFormatter, Formatter, FormatterBuilder, SpanPosition, format_span_context,
-    format_span_context_with, format_with_span_position,
+    DefaultFormatter, Formatter, FormatterBuilder, GelfFormatter, JsonFormatter, LevelStyle,
+    ModuleFormat, MultiFormatter, Newlines, Section, SpanFormat, SpanPosition, TimestampFormat,
+    format_span_context, format_span_context_styled, format_span_context_with, format_thread_id,
+    format_with_span_position,
 };
 pub use hooks::{Hook, set_hook};
 pub use level::LogLevel;
-pub use span::{Span, SpanGuard, current_context, enter};
-pub use strings::{Color, Colorize, Style};
-pub use target::{Console, File, FileMode, Output, Target, TargetId};
-
-#[cfg(feature = "blocking")]
-pub use r#impl::blocking::DefaultLogger;
+pub use record::{Backtrace, Record, RecordBuilder};
+pub use shutdown::shutdown;
+pub use span::{
+    Span, SpanGuard, current_context, enter, global_fields, remove_global_field, set_global_field,
+};
+pub use strings::{Color, ColorMode, Colorize, Style, gradient};
+pub use suppress::{SuppressGuard, is_suppressed, suppress, suppress_logging};
+pub use target::{
+    ChannelItem, ChannelTarget, Console, ControlChars, Decorate, File, FileMode, LevelSplit,
+    MemoryTarget, Named, NonBlocking, Output, QueueFull, RollingFile, RotationPeriod, Sampler,
+    ShardedFile, Sharding, SharedWriter, Target, TargetId, WorkerGuard,
+};
+pub use tee::{ExtraTargetGuard, add_extra_target, with_extra_target};
+pub use thread_level::{ThreadLevelGuard, set_thread_level, thread_level, with_thread_level};
+pub use timer::{Timer, human_duration};
+pub use trace_id::{TraceIdGuard, clear_trace_id, set_trace_id, trace_id, with_trace_id};
+pub use unique::__first_time;
 
-#[cfg(not(feature = "blocking"))]
-pub use r#impl::r#async::DefaultLogger;
+#[cfg(any(
+    all(
+        any(target_os = "linux", target_os = "android"),
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv64"
+        )
+    ),
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd"
+))]
+pub use target::FifoTarget;
 
-/// Represents a single log record with all relevant metadata.
-///
-/// A `Record` contains the log level, target component, message content, and
-/// source location information (module path, file, line).
-#[derive(Debug, Clone)]
-pub struct Record {
-    /// The severity level of the log message.
-    pub level: LogLevel,
+#[cfg(all(feature = "journald", target_os = "linux"))]
+pub use target::JournaldTarget;
 
-    /// The thread ID where the log was generated.
-    pub thread_id: ThreadId,
+#[cfg(all(feature = "android", target_os = "android"))]
+pub use target::LogcatTarget;
 
-    /// The target component or category for the log message.
-    pub target: String,
+#[cfg(feature = "otlp")]
+pub use target::OtlpTarget;
 
-    /// The actual log message content.
-    pub message: String,
+#[cfg(feature = "clipboard")]
+pub use target::ClipboardTarget;
 
-    /// Optional module path where the log was generated.
-    pub module_path: Option<&'static str>,
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub use target::WasmConsoleTarget;
 
-    /// Optional source code file where the log was generated.
-    pub file: Option<&'static str>,
+pub use r#impl::null::NullLogger;
 
-    /// Optional line number in the source code where the log was generated.
-    pub line: Option<u32>,
+#[cfg(feature = "blocking")]
+pub use r#impl::blocking::DefaultLogger;
 
-    /// Context information from active spans.
-    pub context: Vec<(String, String)>,
-}
+#[cfg(not(feature = "blocking"))]
+pub use r#impl::r#async::DefaultLogger;
 
 /// Core trait that defines the logging behavior.
 ///
@@ -112,13 +147,53 @@ pub trait Logger: Send + Sync {
     /// * `record` - The log record to process
     fn log(&self, record: &Record);
 
+    /// Process and output a log record, taking ownership of it.
+    ///
+    /// The logging macros call this method, so that loggers keeping the record,
+    /// e.g. to write it from another thread, don't have to copy it.
+    /// The default implementation calls [`Logger::log`].
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The log record to process
+    fn log_owned(&self, record: Record) {
+        self.log(&record);
+    }
+
     /// Abort any ongoing logging operations and cleanup resources.
     ///
     /// This method is only available when not using the "blocking" feature.
     #[cfg(not(feature = "blocking"))]
     fn abort(&self);
+
+    /// Requests all the pending records to be written.
+    ///
+    /// `done` must be called once every record logged before this call
+    /// has been written. The default implementation calls it right away,
+    /// which is correct for loggers that write synchronously.
+    ///
+    /// # Arguments
+    ///
+    /// * `done` - Callback to run when the pending records are written
+    fn flush(&self, done: Box<dyn FnOnce() + Send>) {
+        done();
+    }
+
+    /// Returns the configuration the logger was created with, if any.
+    ///
+    /// Used by [`current_config`]. The default implementation returns `None`,
+    /// for loggers that are not built from a [`Config`].
+    fn config(&self) -> Option<&Config> {
+        None
+    }
 }
 
+/// Closure choosing the targets of each record, see [`Config::router`].
+pub type Router = Box<dyn Fn(&Record) -> Vec<usize> + Send + Sync>;
+
+/// Default value of [`Config::flush_interval`].
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Configuration for initializing a logger.
 ///
 /// This struct allows customizing the logger's behavior by specifying
@@ -131,7 +206,78 @@ pub struct Config {
     pub targets: Vec<Box<dyn Target>>,
 
     /// Optional formatter for customizing log message appearance.
+    ///
+    /// `None` (the default) formats the records with [`DefaultFormatter::new`],
+    /// see [`Config::formatter`].
     pub format: Option<Box<dyn Formatter>>,
+
+    /// Minimum level at which a backtrace is captured and attached to the record.
+    ///
+    /// Capturing is expensive, so this is off (`None`) by default.
+    /// It follows the `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE` environment variables:
+    /// if they disable backtraces, no backtrace is attached.
+    pub capture_backtrace_at: Option<LogLevel>,
+
+    /// How often the async logger flushes the targets while idle, with [`Target::flush`].
+    ///
+    /// Buffered targets commit their data within this interval after a write,
+    /// even if nothing else is logged. Defaults to one second, `None` disables it.
+    /// Ignored by the blocking logger, which doesn't buffer records.
+    pub flush_interval: Option<Duration>,
+
+    /// Optional closure choosing which targets each record is written to.
+    ///
+    /// It returns the indices, in `targets`, of the targets the record goes to.
+    /// The levels of the targets still apply, and out of range indices are ignored.
+    /// When `None` (the default), records go to every target.
+    ///
+    /// The router runs on the thread logging the record, before the record is queued.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{Config, Console, File, FileMode};
+    ///
+    /// let config = Config {
+    ///     targets: vec![
+    ///         Box::new(Console::new()),
+    ///         Box::new(File::new("logs/audit.log", FileMode::Append)?),
+    ///     ],
+    ///     // Payments only go to the audit file
+    ///     router: Some(Box::new(|record| {
+    ///         if record.message.contains("PAYMENT") {
+    ///             vec![1]
+    ///         } else {
+    ///             vec![0]
+    ///         }
+    ///     })),
+    ///     ..Config::default()
+    /// };
+    /// ```
+    pub router: Option<Router>,
+
+    /// Modules whose records are dropped, whatever their level.
+    ///
+    /// A module matches if its path is equal to an entry or starts with it
+    /// followed by `::`, so `mio` silences `mio` and `mio::poll`, but not `miova`.
+    /// Records are dropped by the logging macros before the message is formatted,
+    /// and are not counted by [`counts`](crate::counts()).
+    pub denied_modules: Vec<String>,
+
+    /// Stack size of the async logger worker thread, in bytes.
+    ///
+    /// Defaults to `None`, using the standard library default.
+    /// Embedded users can shrink it, keeping in mind that targets and hooks run on it.
+    /// Ignored by the blocking logger, which has no worker thread.
+    pub worker_stack_size: Option<usize>,
+
+    /// Whether the effective configuration is logged once the logger is installed.
+    ///
+    /// When set, a line describing the level, the formatter and the other settings
+    /// is logged, followed by a line per target, with its id and the levels it accepts.
+    /// The lines go through the logger like any other record, at `INFO`,
+    /// even if the configured level is higher. Defaults to `false`.
+    pub log_config_on_init: bool,
 }
 
 impl Config {
@@ -149,9 +295,126 @@ impl Config {
     pub fn default_with_level(level: LogLevel) -> Self {
         Config {
             level,
-            targets: vec![Box::new(target::Console::new())],
-            format: Some(Box::new(format::DefaultFormatter::new())),
+            targets: vec![Box::new(target::Console::default_target())],
+            format: None,
+            capture_backtrace_at: None,
+            flush_interval: Some(DEFAULT_FLUSH_INTERVAL),
+            router: None,
+            denied_modules: Vec::new(),
+            worker_stack_size: None,
+            log_config_on_init: false,
+        }
+    }
+
+    /// Builder method to set the minimum log level.
+    pub fn with_level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Builder method to set the formatter, replacing the default one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{Config, JsonFormatter};
+    ///
+    /// let config = Config::default().with_format(JsonFormatter::new());
+    /// ```
+    pub fn with_format(mut self, format: impl Formatter + 'static) -> Self {
+        self.format = Some(Box::new(format));
+        self
+    }
+
+    /// Returns the formatter the records are formatted with:
+    /// the one set in [`Config::format`], or [`DefaultFormatter::new`] if there is none.
+    pub fn formatter(&self) -> &dyn Formatter {
+        match &self.format {
+            Some(format) => format.as_ref(),
+            None => &*format::DEFAULT_FORMATTER,
+        }
+    }
+
+    /// Builder method to add a target after the ones already configured.
+    ///
+    /// The console target of the default configuration is replaced by the first target added,
+    /// so `Config::default().with_target(Console::stderr())` only writes to stderr.
+    /// Add a [`Console`] explicitly to keep writing to stdout.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{Config, Console, File, FileMode, LogLevel};
+    ///
+    /// let mut config = Config::default()
+    ///     .with_level(LogLevel::Debug)
+    ///     .with_target(Console::stderr());
+    ///
+    /// if let Some(path) = log_file {
+    ///     config = config.with_target(File::new(path, FileMode::Append)?);
+    /// }
+    ///
+    /// traccia::init_with_config(config);
+    /// ```
+    pub fn with_target(mut self, target: impl Target + 'static) -> Self {
+        if let [target] = self.targets.as_slice()
+            && target.is_default()
+        {
+            self.targets.clear();
+        }
+
+        self.targets.push(Box::new(target));
+        self
+    }
+
+    /// Builder method to drop the records of a module and its submodules.
+    ///
+    /// See [`Config::denied_modules`].
+    pub fn with_denied_module(mut self, module: impl Into<String>) -> Self {
+        self.denied_modules.push(module.into());
+        self
+    }
+
+    /// Checks that the configuration has targets, and that each of them is able to write.
+    ///
+    /// Initialization never fails because of a target, and write errors are only reported
+    /// when they happen. Call this before initializing the logger to fail fast instead,
+    /// e.g. when a log file was removed or made read-only.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the configuration is valid, `Error::NoTargets` if it has no targets,
+    /// or `Error::InvalidTarget` with the error of the first invalid target
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{Config, File, FileMode};
+    ///
+    /// let config = Config::default().with_target(File::new("logs/app.log", FileMode::Append)?);
+    ///
+    /// config.validate()?;
+    /// traccia::init_with_config(config);
+    /// ```
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.targets.is_empty() {
+            return Err(Error::NoTargets);
         }
+
+        for target in &self.targets {
+            target.validate().map_err(|e| Error::InvalidTarget {
+                id: target.id(),
+                source: Box::new(e),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Builder method to remove all the configured targets.
+    pub fn without_targets(mut self) -> Self {
+        self.targets.clear();
+        self
     }
 }
 
@@ -166,8 +429,62 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             level: LogLevel::Info,
-            targets: vec![Box::new(target::Console::new())],
-            format: Some(Box::new(format::DefaultFormatter::new())),
+            targets: vec![Box::new(target::Console::default_target())],
+            format: None,
+            capture_backtrace_at: None,
+            flush_interval: Some(DEFAULT_FLUSH_INTERVAL),
+            router: None,
+            denied_modules: Vec::new(),
+            worker_stack_size: None,
+            log_config_on_init: false,
+        }
+    }
+}
+
+/// Read-only view of a [`Config`], returned by [`current_config`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSnapshot {
+    /// The minimum log level that will be processed.
+    pub level: LogLevel,
+
+    /// Ids of the configured targets, in order.
+    pub targets: Vec<TargetId>,
+
+    /// Whether a formatter is set, instead of the default one.
+    pub has_format: bool,
+
+    /// Minimum level at which a backtrace is captured, if any.
+    pub capture_backtrace_at: Option<LogLevel>,
+
+    /// How often the targets are flushed while idle, if enabled.
+    pub flush_interval: Option<Duration>,
+
+    /// Whether a router is set.
+    pub has_router: bool,
+
+    /// Modules whose records are dropped.
+    pub denied_modules: Vec<String>,
+
+    /// Stack size of the worker thread, if set.
+    pub worker_stack_size: Option<usize>,
+
+    /// Whether the configuration is logged at initialization.
+    pub log_config_on_init: bool,
+}
+
+impl Config {
+    /// Returns a read-only view of the configuration.
+    pub fn snapshot(&self) -> ConfigSnapshot {
+        ConfigSnapshot {
+            level: self.level,
+            targets: self.targets.iter().map(|target| target.id()).collect(),
+            has_format: self.format.is_some(),
+            capture_backtrace_at: self.capture_backtrace_at,
+            flush_interval: self.flush_interval,
+            has_router: self.router.is_some(),
+            denied_modules: self.denied_modules.clone(),
+            worker_stack_size: self.worker_stack_size,
+            log_config_on_init: self.log_config_on_init,
         }
     }
 }
@@ -189,8 +506,17 @@ static LOGGER: OnceLock<Box<dyn Logger>> = OnceLock::new();
 ///
 /// `Ok(())` if successful, or `Error::AlreadyInitialized` if a logger is already set
 fn set_logger<L: Logger + 'static>(logger: L) -> Result<(), Error> {
-    match LOGGER.set(Box::new(logger)) {
-        Ok(_) => {
+    let installed = early::install(|| {
+        LOGGER.set(Box::new(logger)).ok()?;
+
+        // Elapsed timestamps are relative to the initialization
+        util::start_time();
+
+        LOGGER.get().map(|boxed| &**boxed as &dyn Logger)
+    });
+
+    match installed {
+        Some(logger) => {
             #[cfg(not(feature = "blocking"))]
             extern "C" fn abort() {
                 if let Some(logger) = LOGGER.get() {
@@ -201,9 +527,15 @@ fn set_logger<L: Logger + 'static>(logger: L) -> Result<(), Error> {
             #[cfg(not(feature = "blocking"))]
             shutdown::add_hook(abort);
 
+            if let Some(config) = logger.config()
+                && config.log_config_on_init
+            {
+                banner::log_config(config);
+            }
+
             Ok(())
         }
-        Err(_) => Err(Error::AlreadyInitialized),
+        None => Err(Error::AlreadyInitialized),
     }
 }
 
@@ -219,6 +551,174 @@ pub fn logger() -> Result<&'static dyn Logger, Error> {
         .ok_or(Error::NotInitialized)
 }
 
+/// Returns `true` if the global logger has been initialized.
+///
+/// Useful for libraries that only set up logging when the application didn't.
+/// Note that two threads may both see `false` and try to initialize the logger,
+/// prefer [`try_init_with_config`] when that matters.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// if !traccia::is_initialized() {
+///     traccia::init_default();
+/// }
+/// ```
+pub fn is_initialized() -> bool {
+    LOGGER.get().is_some()
+}
+
+/// Returns a read-only view of the configuration of the global logger.
+///
+/// Returns `None` if the logger is not initialized,
+/// or if it is a custom logger not built from a [`Config`].
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// traccia::init(traccia::LogLevel::Debug);
+///
+/// if let Some(config) = traccia::current_config() {
+///     traccia::debug!("Logging at {} to {:?}", config.level, config.targets);
+/// }
+/// ```
+pub fn current_config() -> Option<ConfigSnapshot> {
+    LOGGER.get()?.config().map(Config::snapshot)
+}
+
+/// Formats a record the way the global logger does, with its configured formatter.
+///
+/// Falls back to [`DefaultFormatter`] if the logger is not initialized,
+/// has no formatter, or is a custom logger not built from a [`Config`].
+/// Useful in custom targets or auxiliary code that need to render records consistently.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{LogLevel, Record};
+///
+/// let record = Record::builder(LogLevel::Info, "replayed").build();
+/// println!("{}", traccia::format_record(&record));
+/// ```
+pub fn format_record(record: &Record) -> String {
+    match LOGGER.get().and_then(|logger| logger.config()) {
+        Some(config) => r#impl::format(config, record),
+        None => format::DEFAULT_FORMATTER.format(record),
+    }
+}
+
+/// Returns `true` if a record at `level` is logged by the global logger,
+/// or buffered until it is initialized.
+///
+/// Records logged while [`suppress`] is active on the current thread, from a hook,
+/// or from a module in [`Config::denied_modules`] are rejected.
+/// Records rejected by the logger level are counted as suppressed.
+/// This is used by the logging macros to skip building the records that would be dropped,
+/// and is not meant to be called directly.
+#[doc(hidden)]
+pub fn __enabled(level: LogLevel, module_path: &str) -> bool {
+    if suppress::is_suppressed() || hooks::reentrant() {
+        return false;
+    }
+
+    match LOGGER.get() {
+        Some(logger) if denies(&**logger, Some(module_path)) => false,
+        Some(logger) if logger.enabled(level) => true,
+        Some(_) => {
+            counts::suppress();
+            false
+        }
+        None => early::accepts(),
+    }
+}
+
+/// Sends a record to the global logger.
+///
+/// Records logged while [`suppress`] is active on the current thread are dropped.
+/// Accepted records are also written to the extra targets of the current thread
+/// (see [`with_extra_target`]).
+///
+/// If no logger is set yet, the record is handled according to
+/// [`buffer_early_logs`] and [`warn_if_uninitialized`].
+///
+/// This is used by the logging macros and is not meant to be called directly.
+#[doc(hidden)]
+pub fn __log(record: Record) {
+    if suppress::is_suppressed() || hooks::reentrant() {
+        return;
+    }
+
+    // Until the buffered records are replayed, records wait for the replay,
+    // so they are written after the ones logged before initialization
+    let record = if early::replayed() {
+        record
+    } else {
+        match early::handle(record) {
+            Some(record) => record,
+            None => return,
+        }
+    };
+
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+
+    if accepts(&**logger, &record) {
+        tee::write(&record);
+        logger.log_owned(record);
+    }
+}
+
+/// Sends a record to the given logger, building it only if the logger accepts its level and module.
+///
+/// This is used by the [`log_to!`] macro and is not meant to be called directly.
+#[doc(hidden)]
+pub fn __log_to<L, F>(logger: &L, level: LogLevel, module_path: &str, record: F)
+where
+    L: Logger + ?Sized,
+    F: FnOnce() -> Record,
+{
+    if suppress::is_suppressed() || hooks::reentrant() || denies(logger, Some(module_path)) {
+        return;
+    }
+
+    if !logger.enabled(level) {
+        counts::suppress();
+        return;
+    }
+
+    counts::increment(level);
+    logger.log_owned(record());
+}
+
+/// Returns `true` if the logger accepts the record, by module and level,
+/// updating the per-level counts or the count of suppressed records.
+///
+/// Every record reaching the global logger goes through here,
+/// including the ones replayed from the early buffer.
+pub(crate) fn accepts(logger: &dyn Logger, record: &Record) -> bool {
+    if denies(logger, record.module_path) {
+        return false;
+    }
+
+    if !logger.enabled(record.level) {
+        counts::suppress();
+        return false;
+    }
+
+    counts::increment(record.level);
+    true
+}
+
+/// Returns `true` if the module is in the [`Config::denied_modules`] of the logger.
+///
+/// Records of denied modules are dropped without being counted.
+fn denies<L: Logger + ?Sized>(logger: &L, module_path: Option<&str>) -> bool {
+    logger
+        .config()
+        .is_some_and(|config| r#impl::denied(config, module_path))
+}
+
 /// Initializes the global logger with the specified minimum log level.
 ///
 /// This function creates a logger with default configuration except for the
@@ -254,6 +754,10 @@ pub fn init_default() {
 ///
 /// This function allows full customization of the logger behavior.
 ///
+/// If the configuration has no targets, a warning is printed to stderr,
+/// as nothing would be logged. Use [`try_init_with_config`] to get an error instead,
+/// or [`init_null`] to disable logging on purpose.
+///
 /// # Arguments
 ///
 /// * `config` - The configuration to use
@@ -262,10 +766,48 @@ pub fn init_default() {
 ///
 /// Panics if a logger is already initialized
 pub fn init_with_config(config: Config) {
+    if config.targets.is_empty() {
+        eprintln!("traccia: {}.", Error::NoTargets);
+    }
+
     let logger = DefaultLogger::new(config);
     set_logger(logger).expect("Failed to initalize logger");
 }
 
+/// Initializes the global logger with a custom configuration,
+/// returning an error instead of panicking.
+///
+/// # Arguments
+///
+/// * `config` - The configuration to use
+///
+/// # Returns
+///
+/// `Ok(())` if successful, `Error::NoTargets` if the configuration has no targets,
+/// or `Error::AlreadyInitialized` if a logger is already set
+pub fn try_init_with_config(config: Config) -> Result<(), Error> {
+    if config.targets.is_empty() {
+        return Err(Error::NoTargets);
+    }
+
+    if LOGGER.get().is_some() {
+        return Err(Error::AlreadyInitialized);
+    }
+
+    set_logger(DefaultLogger::new(config))
+}
+
+/// Initializes the global logger with a [`NullLogger`], that discards every record.
+///
+/// No worker thread is spawned and no target is opened.
+///
+/// # Panics
+///
+/// Panics if a logger is already initialized
+pub fn init_null() {
+    set_logger(NullLogger).expect("Failed to initalize logger");
+}
+
 /// Checks the `RUST_LOG` env variable
 /// and tries to parse its value
 ///
@@ -273,3 +815,44 @@ pub fn init_with_config(config: Config) {
 pub fn parse_level_from_env() -> Option<LogLevel> {
     std::env::var("RUST_LOG").ok().and_then(|s| s.parse().ok())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_level() {
+        let config = Config::default().with_level(LogLevel::Debug);
+
+        assert_eq!(config.level, LogLevel::Debug);
+        assert_eq!(
+            config.snapshot().targets,
+            [TargetId::Console(Output::Stdout)]
+        );
+    }
+
+    #[test]
+    fn test_with_target_replaces_default_console() {
+        let config = Config::default()
+            .with_level(LogLevel::Warn)
+            .with_target(Console::stderr());
+
+        assert_eq!(config.level, LogLevel::Warn);
+        assert_eq!(
+            config.snapshot().targets,
+            [TargetId::Console(Output::Stderr)]
+        );
+
+        let config = Config::default()
+            .with_target(Console::new())
+            .with_target(Console::stderr());
+
+        assert_eq!(
+            config.snapshot().targets,
+            [
+                TargetId::Console(Output::Stdout),
+                TargetId::Console(Output::Stderr)
+            ]
+        );
+    }
+}
@@ -1,31 +1,215 @@
 /// Macro for logging messages at a specific level.
 ///
 /// This is the core logging macro that other macros (`debug!`, `info!`, etc.) build upon.
+/// The message is only formatted if the record is logged, or buffered
+/// with [`buffer_early_logs`](crate::buffer_early_logs) before initialization.
 ///
 /// # Arguments
 ///
 /// * `$level` - The log level to use
 /// * `$arg` - Format string and arguments, similar to `format!` or `println!`
+///
+/// # Examples
+///
+/// ```
+/// use traccia::{LogLevel, init_default, log};
+///
+/// const NOTICE: LogLevel = LogLevel::custom("NOTICE", 2);
+///
+/// init_default();
+/// log!(NOTICE, "Certificate expires in {} days", 30);
+/// ```
 #[macro_export]
 macro_rules! log {
    ($level:expr, $($arg:tt)*) => {{
-        if let Ok(logger) = $crate::logger() {
-            let record = $crate::Record {
-                level: $level,
+        let level = $level;
+
+        if $crate::__enabled(level, module_path!()) {
+            $crate::__log($crate::Record {
+                level,
                 thread_id: std::thread::current().id(),
                 target: module_path!().to_string(),
                 message: format!($($arg)*),
                 module_path: Some(module_path!()),
                 file: Some(file!()),
                 line: Some(line!()),
-                context: $crate::current_context(),
-            };
+                context: $crate::Record::collect_context(Vec::new()),
+                trace_id: $crate::trace_id(),
+                backtrace: None,
+            });
+        }
+    }};
+}
 
-            logger.log(&record);
+/// Macro for logging messages to a specific logger, with static dispatch.
+///
+/// Unlike [`log!`], which goes through the global `Box<dyn Logger>`, the logger
+/// is passed as a reference to its concrete type, so the calls are monomorphized.
+/// The record is only built if the logger accepts the level.
+/// This is meant for latency critical paths, with the logger stored in a `static`.
+///
+/// Records logged this way are counted by [`counts`](crate::counts())
+/// and dropped within [`suppress`](crate::suppress()), but don't go through
+/// the early buffer, as the logger always exists.
+///
+/// # Arguments
+///
+/// * `$logger` - Reference to the logger, e.g. `&*LOGGER`
+/// * `$level` - The log level to use
+/// * `$arg` - Format string and arguments, similar to `format!` or `println!`
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::LazyLock;
+/// use traccia::{Config, DefaultLogger, LogLevel, log_to};
+///
+/// static HOT: LazyLock<DefaultLogger> =
+///     LazyLock::new(|| DefaultLogger::new(Config::default_with_level(LogLevel::Warn)));
+///
+/// log_to!(&*HOT, LogLevel::Debug, "Not even formatted: {}", 42);
+/// log_to!(&*HOT, LogLevel::Warn, "Queue is {}% full", 90);
+/// ```
+#[macro_export]
+macro_rules! log_to {
+    ($logger:expr, $level:expr, $($arg:tt)*) => {{
+        let level = $level;
+
+        $crate::__log_to($logger, level, module_path!(), || $crate::Record {
+            level,
+            thread_id: std::thread::current().id(),
+            target: module_path!().to_string(),
+            message: format!($($arg)*),
+            module_path: Some(module_path!()),
+            file: Some(file!()),
+            line: Some(line!()),
+            context: $crate::Record::collect_context(Vec::new()),
+            trace_id: $crate::trace_id(),
+            backtrace: None,
+        });
+    }};
+}
+
+/// Logs an error along with its chain of sources.
+///
+/// The message is the error followed by each of its sources,
+/// as rendered by [`error_chain`](crate::error_chain), optionally preceded by a context message.
+///
+/// # Syntax
+///
+/// ```ignore
+/// log_error_chain!(level, err)
+/// log_error_chain!(level, err, "context {}", args...)
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use traccia::{LogLevel, init_default, log_error_chain};
+///
+/// init_default();
+///
+/// if let Err(err) = std::fs::read_to_string("missing.toml") {
+///     log_error_chain!(LogLevel::Error, err, "Failed to load {}", "missing.toml");
+///     // Logs: [ERROR] Failed to load missing.toml: No such file or directory (os error 2)
+/// }
+/// ```
+#[macro_export]
+macro_rules! log_error_chain {
+    ($level:expr, $err:expr) => {
+        $crate::log!($level, "{}", $crate::error_chain(&$err))
+    };
+    ($level:expr, $err:expr, $($arg:tt)+) => {
+        $crate::log!($level, "{}: {}", format!($($arg)+), $crate::error_chain(&$err))
+    };
+}
+
+/// Logs a message only the first time it is logged with the same level and text.
+///
+/// The message is formatted first, so `x=1` and `x=2` are distinct messages:
+/// unlike a per call-site guard, each distinct message is logged once,
+/// whichever call site logs it. Useful for warnings that would otherwise
+/// repeat, such as deprecation notices.
+///
+/// Up to 4096 distinct messages are remembered for the lifetime of the process.
+/// Past that, the oldest ones are forgotten and may be logged again.
+/// Nothing is formatted or remembered if the record would be dropped: when the global
+/// logger rejects the level or the module, within [`suppress`](crate::suppress()), from a hook,
+/// or before initialization unless [`buffer_early_logs`](crate::buffer_early_logs) is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use traccia::{LogLevel, init_default, log_unique};
+///
+/// init_default();
+///
+/// for key in ["timeout", "retries", "timeout"] {
+///     // Logged twice, once for each key
+///     log_unique!(LogLevel::Warn, "Config key `{}` is deprecated", key);
+/// }
+/// ```
+#[macro_export]
+macro_rules! log_unique {
+    ($level:expr, $($arg:tt)+) => {{
+        let level = $level;
+
+        if $crate::__enabled(level, module_path!()) {
+            let message = format!($($arg)+);
+
+            if $crate::__first_time(level, &message) {
+                $crate::log!(level, "{}", message);
+            }
         }
     }};
 }
 
+/// Logs bytes as a hex dump, rendered by [`hex_dump`](crate::hex_dump).
+///
+/// The first line holds the number of bytes, optionally preceded by a message,
+/// and the dump follows on the next lines.
+/// As with [`log!`](crate::log), the dump is only rendered if the record is logged.
+///
+/// `bytes` can be anything implementing `AsRef<[u8]>`, such as a slice, an array or a `Vec<u8>`.
+///
+/// # Syntax
+///
+/// ```ignore
+/// hexdump!(level, bytes)
+/// hexdump!(level, bytes, "message {}", args...)
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use traccia::{LogLevel, hexdump, init};
+///
+/// init(LogLevel::Debug);
+///
+/// let packet = [0x16, 0x03, 0x01, 0x00, 0x2e];
+///
+/// hexdump!(LogLevel::Debug, packet, "Received from {}", "10.0.0.2");
+/// // [DEBUG] Received from 10.0.0.2 (5 bytes)
+/// // 00000000: 1603 0100 2e                             .....
+/// ```
+#[macro_export]
+macro_rules! hexdump {
+    ($level:expr, $bytes:expr) => {{
+        let bytes: &[u8] = ::core::convert::AsRef::<[u8]>::as_ref(&$bytes);
+        $crate::log!($level, "{} bytes\n{}", bytes.len(), $crate::hex_dump(bytes));
+    }};
+    ($level:expr, $bytes:expr, $($arg:tt)+) => {{
+        let bytes: &[u8] = ::core::convert::AsRef::<[u8]>::as_ref(&$bytes);
+        $crate::log!(
+            $level,
+            "{} ({} bytes)\n{}",
+            format!($($arg)+),
+            bytes.len(),
+            $crate::hex_dump(bytes)
+        );
+    }};
+}
+
 /// Logs a message at the TRACE level.
 ///
 /// # Examples
@@ -182,3 +366,39 @@ macro_rules! span {
         ),+])
     };
 }
+
+/// Starts a scoped timer that logs the elapsed time when dropped.
+///
+/// The returned [`Timer`](crate::Timer) guard must be bound to a variable,
+/// otherwise it is dropped (and logged) immediately.
+/// When no level is given, `LogLevel::Debug` is used.
+///
+/// # Syntax
+///
+/// ```ignore
+/// timed!(label)
+/// timed!(level, label)
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use traccia::{LogLevel, init_default, timed};
+///
+/// init_default();
+///
+/// fn load_config() {
+///     let _t = timed!(LogLevel::Info, "loading config");
+///     // ...
+/// }
+/// // Logs: [INFO] loading config took 12.30µs
+/// ```
+#[macro_export]
+macro_rules! timed {
+    ($label:expr) => {
+        $crate::timed!($crate::LogLevel::Debug, $label)
+    };
+    ($level:expr, $label:expr) => {
+        $crate::Timer::new($level, $label).module_path(module_path!())
+    };
+}
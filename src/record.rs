@@ -0,0 +1,498 @@
+//! Log records and a builder to create them by hand.
+
+use crate::{LogLevel, Span, span, trace_id};
+use std::{
+    backtrace::BacktraceStatus,
+    fmt,
+    ops::Deref,
+    sync::{Arc, OnceLock},
+    thread::{self, ThreadId},
+};
+
+/// Name of the span holding the global fields, see [`Record::collect_context`].
+const GLOBAL_SPAN: &str = "global";
+
+/// Name of the span holding the fields of the call, see [`Record::collect_context`].
+const FIELDS_SPAN: &str = "fields";
+
+/// Represents a single log record with all relevant metadata.
+///
+/// A `Record` contains the log level, target component, message content,
+/// source location information (module path, file, line), the span context,
+/// the trace id and, when captured, a backtrace.
+///
+/// All the fields are public and part of the stable API, so custom formatters
+/// and custom [`Logger`](crate::Logger) implementations can rely on them.
+/// In particular, [`Record::context`] holds the spans that were active when the record
+/// was created, each with its name and fields, as returned by [`current_context`](crate::current_context).
+#[derive(Debug, Clone)]
+pub struct Record {
+    /// The severity level of the log message.
+    pub level: LogLevel,
+
+    /// The thread ID where the log was generated.
+    pub thread_id: ThreadId,
+
+    /// The target component or category for the log message.
+    pub target: String,
+
+    /// The actual log message content.
+    pub message: String,
+
+    /// Optional module path where the log was generated.
+    pub module_path: Option<&'static str>,
+
+    /// Optional source code file where the log was generated.
+    pub file: Option<&'static str>,
+
+    /// Optional line number in the source code where the log was generated.
+    pub line: Option<u32>,
+
+    /// Context information from active spans,
+    /// ordered from the outermost to the innermost span.
+    ///
+    /// Filled by the logging macros. Each span keeps its fields grouped,
+    /// as `(key, value)` pairs in the order they were declared.
+    pub context: Vec<Span>,
+
+    /// Correlation id of the thread when the record was created,
+    /// set with [`set_trace_id`](crate::set_trace_id).
+    pub trace_id: Option<String>,
+
+    /// Backtrace of the call site, captured only when
+    /// [`Config::capture_backtrace_at`](crate::Config::capture_backtrace_at)
+    /// is set and the level reaches it.
+    pub backtrace: Option<Backtrace>,
+}
+
+impl Record {
+    /// Creates a builder for a record with the given level and message.
+    ///
+    /// Useful to create records outside of the logging macros,
+    /// for example when bridging from another logging system, replaying logs or testing.
+    /// Every other field gets a sensible default: the current thread id and trace id,
+    /// an empty target, no location and no context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::{LogLevel, Record};
+    ///
+    /// let record = Record::builder(LogLevel::Warn, "disk almost full")
+    ///     .target("storage")
+    ///     .line(42)
+    ///     .build();
+    ///
+    /// assert_eq!(record.target, "storage");
+    /// assert_eq!(record.line, Some(42));
+    /// assert!(record.context.is_empty());
+    /// ```
+    pub fn builder(level: LogLevel, message: impl Into<String>) -> RecordBuilder {
+        RecordBuilder {
+            record: Record {
+                level,
+                thread_id: thread::current().id(),
+                target: String::new(),
+                message: message.into(),
+                module_path: None,
+                file: None,
+                line: None,
+                context: Vec::new(),
+                trace_id: trace_id::trace_id(),
+                backtrace: None,
+            },
+        }
+    }
+
+    /// Builds the context of a record created now, on the current thread.
+    ///
+    /// The context is made of, in order:
+    ///
+    /// 1. the global fields set with [`set_global_field`](crate::set_global_field),
+    ///    in a span named `global`
+    /// 2. the active spans, from the outermost to the innermost
+    /// 3. `fields`, the fields of this call, in a span named `fields`
+    ///
+    /// Empty groups are left out. Later entries take precedence over earlier ones:
+    /// formatters merging fields by key, such as [`JsonFormatter`](crate::JsonFormatter),
+    /// keep the value of the innermost span.
+    ///
+    /// This is what the logging macros use, and what custom loggers and bridges
+    /// should use to get the same context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::{LogLevel, Record};
+    ///
+    /// traccia::set_global_field("service", "billing");
+    /// let _span = traccia::span!("request", "id" => 42);
+    ///
+    /// let record = Record::builder(LogLevel::Info, "charged")
+    ///     .context(Record::collect_context(vec![("amount".to_string(), "10".to_string())]))
+    ///     .build();
+    ///
+    /// let names = record.context.iter().map(|span| span.name.as_str()).collect::<Vec<_>>();
+    /// assert_eq!(names, ["global", "request", "fields"]);
+    /// # traccia::remove_global_field("service");
+    /// ```
+    pub fn collect_context(fields: Vec<(String, String)>) -> Vec<Span> {
+        let global = span::global_fields();
+        let mut context = span::current_context();
+
+        if !global.is_empty() {
+            context.insert(0, Span::synthetic(GLOBAL_SPAN, global));
+        }
+
+        if !fields.is_empty() {
+            context.push(Span::synthetic(FIELDS_SPAN, fields));
+        }
+
+        context
+    }
+
+    /// Returns the number of active spans the record was logged in.
+    ///
+    /// The `global` and `fields` spans added by [`Record::collect_context`]
+    /// are not counted, so a record logged outside of any span has a depth of 0.
+    /// Spans that were entered are always counted, even if they share these names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::{LogLevel, Record};
+    ///
+    /// let _outer = traccia::span!("request");
+    /// let _inner = traccia::span!("db", "table" => "users");
+    ///
+    /// let record = Record::builder(LogLevel::Info, "query")
+    ///     .context(Record::collect_context(vec![("rows".to_string(), "3".to_string())]))
+    ///     .build();
+    ///
+    /// assert_eq!(record.span_depth(), 2);
+    /// ```
+    pub fn span_depth(&self) -> usize {
+        self.context
+            .iter()
+            .filter(|span| !span.is_synthetic())
+            .count()
+    }
+
+    /// Returns the value of the field with the given key,
+    /// looking from the innermost span to the outermost one.
+    ///
+    /// Like the formatters merging fields by key, the innermost value wins,
+    /// so the fields of the call override the spans, which override the global fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::{LogLevel, Record, Span};
+    ///
+    /// let record = Record::builder(LogLevel::Info, "retrying")
+    ///     .context(vec![
+    ///         Span::new("request", vec![("id".to_string(), "42".to_string())]),
+    ///         Span::new("attempt", vec![("id".to_string(), "3".to_string())]),
+    ///     ])
+    ///     .build();
+    ///
+    /// assert_eq!(record.field("id"), Some("3"));
+    /// assert_eq!(record.field("user"), None);
+    /// ```
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.context.iter().rev().find_map(|span| span.get(key))
+    }
+
+    /// Returns every field of the context as `(span name, key, value)`,
+    /// from the outermost span to the innermost one.
+    ///
+    /// Useful in custom formatters that render each field on its own,
+    /// e.g. as `span.key=value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::{LogLevel, Record, Span};
+    ///
+    /// let record = Record::builder(LogLevel::Info, "query")
+    ///     .context(vec![Span::new("db", vec![("table".to_string(), "users".to_string())])])
+    ///     .build();
+    ///
+    /// let fields = record
+    ///     .fields()
+    ///     .map(|(span, key, value)| format!("{}.{}={}", span, key, value))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(fields, ["db.table=users"]);
+    /// ```
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str, &str)> {
+        self.context.iter().flat_map(|span| {
+            span.fields
+                .iter()
+                .map(|(key, value)| (span.name.as_str(), key.as_str(), value.as_str()))
+        })
+    }
+
+    /// Returns the last `depth` segments of the module path,
+    /// e.g. `http::handler` for `my_app::net::http::handler` with a depth of 2.
+    ///
+    /// A depth of `0` is treated as `1`, returning the leaf module.
+    /// Returns `None` if the record has no module path, or an empty one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::{LogLevel, Record};
+    ///
+    /// let record = Record::builder(LogLevel::Info, "request handled")
+    ///     .module_path("my_app::net::http::handler")
+    ///     .build();
+    ///
+    /// assert_eq!(record.short_module(1), Some("handler"));
+    /// assert_eq!(record.short_module(2), Some("http::handler"));
+    /// assert_eq!(record.short_module(10), Some("my_app::net::http::handler"));
+    /// ```
+    pub fn short_module(&self, depth: usize) -> Option<&'static str> {
+        let module_path = self.module_path.filter(|path| !path.is_empty())?;
+
+        let start = module_path
+            .rmatch_indices("::")
+            .nth(depth.max(1) - 1)
+            .map_or(0, |(index, _)| index + 2);
+
+        Some(&module_path[start..])
+    }
+
+    /// Returns the first segment of the module path, which is the crate name.
+    ///
+    /// Returns `None` if the record has no module path, or an empty one.
+    pub fn crate_name(&self) -> Option<&'static str> {
+        let module_path = self.module_path.filter(|path| !path.is_empty())?;
+
+        module_path.split("::").next()
+    }
+}
+
+/// Builder for [`Record`], created with [`Record::builder`].
+#[must_use]
+#[derive(Debug, Clone)]
+pub struct RecordBuilder {
+    record: Record,
+}
+
+impl RecordBuilder {
+    /// Sets the thread id, defaults to the thread that created the builder.
+    pub fn thread_id(mut self, thread_id: ThreadId) -> Self {
+        self.record.thread_id = thread_id;
+        self
+    }
+
+    /// Sets the target component or category.
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.record.target = target.into();
+        self
+    }
+
+    /// Sets the module path where the record was generated.
+    pub fn module_path(mut self, module_path: &'static str) -> Self {
+        self.record.module_path = Some(module_path);
+        self
+    }
+
+    /// Sets the source file where the record was generated.
+    pub fn file(mut self, file: &'static str) -> Self {
+        self.record.file = Some(file);
+        self
+    }
+
+    /// Sets the line where the record was generated.
+    pub fn line(mut self, line: u32) -> Self {
+        self.record.line = Some(line);
+        self
+    }
+
+    /// Sets the span context, from the outermost to the innermost span.
+    pub fn context(mut self, context: Vec<Span>) -> Self {
+        self.record.context = context;
+        self
+    }
+
+    /// Sets the trace id, defaults to the one of the thread that created the builder.
+    pub fn trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.record.trace_id = Some(trace_id.into());
+        self
+    }
+
+    /// Sets the backtrace text.
+    ///
+    /// See [`Config::capture_backtrace_at`](crate::Config::capture_backtrace_at) for automatic capturing.
+    pub fn backtrace(mut self, backtrace: impl Into<String>) -> Self {
+        self.record.backtrace = Some(Backtrace::from(backtrace.into()));
+        self
+    }
+
+    /// Builds the record.
+    pub fn build(self) -> Record {
+        self.record
+    }
+}
+
+/// Backtrace attached to a [`Record`].
+///
+/// A backtrace captured by the logger is only rendered to text the first time it is read,
+/// so formatters and targets that don't print it don't pay for resolving the symbols.
+/// The frames of traccia itself, from the logging macro down to the capture, are left out.
+///
+/// Dereferences to the rendered text, e.g. `backtrace.lines()`.
+#[derive(Clone)]
+pub struct Backtrace(Arc<BacktraceInner>);
+
+enum BacktraceInner {
+    Captured(std::backtrace::Backtrace, OnceLock<String>),
+    Text(String),
+}
+
+impl Backtrace {
+    /// Captures the backtrace of the current thread.
+    ///
+    /// Returns `None` when backtraces are disabled by the environment,
+    /// see [`std::backtrace::Backtrace::capture`].
+    pub(crate) fn capture() -> Option<Self> {
+        let backtrace = std::backtrace::Backtrace::capture();
+
+        if backtrace.status() != BacktraceStatus::Captured {
+            return None;
+        }
+
+        Some(Self(Arc::new(BacktraceInner::Captured(
+            backtrace,
+            OnceLock::new(),
+        ))))
+    }
+
+    /// Returns the text of the backtrace, one line per frame and location.
+    pub fn as_str(&self) -> &str {
+        match &*self.0 {
+            BacktraceInner::Captured(backtrace, text) => {
+                text.get_or_init(|| without_internal_frames(&backtrace.to_string()))
+            }
+            BacktraceInner::Text(text) => text,
+        }
+    }
+}
+
+impl From<String> for Backtrace {
+    fn from(text: String) -> Self {
+        Self(Arc::new(BacktraceInner::Text(text)))
+    }
+}
+
+impl From<&str> for Backtrace {
+    fn from(text: &str) -> Self {
+        Self::from(text.to_string())
+    }
+}
+
+impl Deref for Backtrace {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Removes the leading frames of the capture itself and of traccia from a rendered
+/// backtrace, renumbering the frames left from zero.
+///
+/// Frames are rendered as `   3: symbol`, followed by their `at file:line` locations.
+fn without_internal_frames(rendered: &str) -> String {
+    let mut lines = Vec::new();
+    let mut index = 0;
+    let mut skipping = true;
+    let mut keep = false;
+
+    for line in rendered.lines() {
+        let frame = line
+            .trim_start()
+            .split_once(": ")
+            .filter(|(number, _)| number.bytes().all(|b| b.is_ascii_digit()));
+
+        if let Some((_, symbol)) = frame {
+            skipping = skipping && is_internal(symbol);
+            keep = !skipping;
+
+            if keep {
+                lines.push(format!("{:>4}: {}", index, symbol));
+                index += 1;
+            }
+        } else if keep {
+            lines.push(line.to_string());
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Returns `true` if the symbol belongs to the capture or to traccia.
+fn is_internal(symbol: &str) -> bool {
+    let symbol = symbol.trim_start_matches('<');
+
+    symbol.starts_with("std::backtrace")
+        || symbol.starts_with(concat!(env!("CARGO_CRATE_NAME"), "::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_depth_counts_entered_spans_named_like_synthetic_ones() {
+        let _global = crate::span!("global");
+        let _fields = crate::span!("fields", "id" => 1);
+
+        let record = Record::builder(LogLevel::Info, "message")
+            .context(Record::collect_context(vec![(
+                "rows".to_string(),
+                "3".to_string(),
+            )]))
+            .build();
+
+        assert_eq!(record.span_depth(), 2);
+        assert_eq!(record.context.len(), 3);
+    }
+
+    #[test]
+    fn test_backtrace_skips_internal_frames() {
+        let rendered = "   0: std::backtrace_rs::backtrace::libunwind::trace
+             at /rustc/library/std/src/../../backtrace/src/backtrace/libunwind.rs:117:9
+   1: std::backtrace::Backtrace::create
+   2: traccia::record::Backtrace::capture
+             at ./src/record.rs:360:25
+   3: <traccia::impl::async::DefaultLogger as traccia::Logger>::log_owned
+   4: app::handle
+             at ./src/main.rs:12:5
+   5: traccia::__log
+   6: main";
+
+        assert_eq!(
+            without_internal_frames(rendered),
+            "   0: app::handle\n             at ./src/main.rs:12:5\n   1: traccia::__log\n   2: main"
+        );
+
+        let backtrace = Backtrace::from("0: main");
+        assert_eq!(backtrace.lines().collect::<Vec<_>>(), ["0: main"]);
+        assert_eq!(format!("{:?}", backtrace.clone()), "\"0: main\"");
+    }
+}
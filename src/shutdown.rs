@@ -1,13 +1,18 @@
-//! Simple wrapper around `atexit` to allow for a single function to be called when the program exits.
+//! Shutdown of the global logger.
 //!
-//! Used for flushing logs and other cleanup tasks.
+//! With the async logger, a function registered with `atexit` when the logger
+//! is initialized writes the pending records and joins the worker thread.
+//! [`shutdown`] does the same explicitly.
 
+#[cfg(not(feature = "blocking"))]
 use std::ffi::c_int;
 
+#[cfg(not(feature = "blocking"))]
 unsafe extern "C" {
     fn atexit(callback: extern "C" fn()) -> c_int;
 }
 
+#[cfg(not(feature = "blocking"))]
 pub fn add_hook(cb: extern "C" fn()) -> bool {
     let result: c_int;
 
@@ -17,3 +22,38 @@ pub fn add_hook(cb: extern "C" fn()) -> bool {
 
     result == 0
 }
+
+/// Writes the pending records, flushes the targets and stops the worker thread,
+/// blocking until it is done.
+///
+/// Records logged after this call are dropped. Calling it more than once is harmless,
+/// and it returns immediately if the logger is not initialized.
+/// With the `blocking` feature, records are written right away, so this does nothing.
+///
+/// The logger is already stopped this way on the exit paths going through
+/// the C `exit` function:
+///
+/// - returning from `main`, including after a panic unwinding out of it
+/// - [`std::process::exit`], from any thread
+///
+/// It is not on the paths terminating the process right away, where pending records
+/// are lost unless this is called first:
+///
+/// - [`std::process::abort`], including panics with `panic = "abort"`
+/// - signals without a handler calling `exit`, such as `SIGKILL`, `SIGINT` or `SIGTERM`
+/// - `libc::_exit` and crashes
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// traccia::error!("Unrecoverable error, exiting");
+///
+/// traccia::shutdown();
+/// std::process::abort();
+/// ```
+pub fn shutdown() {
+    #[cfg(not(feature = "blocking"))]
+    if let Some(logger) = crate::LOGGER.get() {
+        logger.abort();
+    }
+}
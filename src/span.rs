@@ -27,16 +27,21 @@
 //! }
 //! ```
 
-use std::cell::RefCell;
+use crate::hooks;
+use std::{cell::RefCell, marker::PhantomData, sync::RwLock, time::Instant};
 
 /// Represents a single span with a name and key-value pairs.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Span {
     /// The name of the span (e.g., "request", "database_query").
     pub name: String,
 
     /// Key-value pairs of context information.
     pub fields: Vec<(String, String)>,
+
+    /// Whether the span was added by [`Record::collect_context`](crate::Record::collect_context)
+    /// to hold the global or per-call fields, rather than entered.
+    synthetic: bool,
 }
 
 impl Span {
@@ -45,8 +50,35 @@ impl Span {
         Self {
             name: name.into(),
             fields,
+            synthetic: false,
+        }
+    }
+
+    /// Creates a span holding the global or per-call fields of a record.
+    pub(crate) fn synthetic(name: &str, fields: Vec<(String, String)>) -> Self {
+        Self {
+            synthetic: true,
+            ..Self::new(name, fields)
         }
     }
+
+    /// Returns `true` if the span holds the global or per-call fields of a record,
+    /// added by [`Record::collect_context`](crate::Record::collect_context), rather than
+    /// a span that was entered.
+    pub fn is_synthetic(&self) -> bool {
+        self.synthetic
+    }
+
+    /// Returns the value of the field with the given key, if the span has one.
+    ///
+    /// If the key was recorded more than once, the last value is returned.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
 }
 
 thread_local! {
@@ -54,50 +86,146 @@ thread_local! {
     static SPAN_STACK: RefCell<Vec<Span>> = const { RefCell::new(Vec::new()) };
 }
 
+/// Fields added to every record, set with [`set_global_field`].
+static GLOBAL_FIELDS: RwLock<Vec<(String, String)>> = RwLock::new(Vec::new());
+
+/// Sets a field added to the context of every record, on every thread,
+/// e.g. the service name or the deployment environment.
+///
+/// Setting a key again replaces its value. Global fields are grouped
+/// in a span named `global`, placed before the active spans.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// traccia::set_global_field("service", "billing");
+///
+/// traccia::info!("Started");
+/// // Logs: [INFO] Started [global: service=billing]
+/// ```
+pub fn set_global_field(key: impl Into<String>, value: impl ToString) {
+    let key = key.into();
+    let value = value.to_string();
+
+    if let Ok(mut fields) = GLOBAL_FIELDS.write() {
+        match fields.iter_mut().find(|(k, _)| *k == key) {
+            Some(field) => field.1 = value,
+            None => fields.push((key, value)),
+        }
+    }
+}
+
+/// Removes a field set with [`set_global_field`].
+pub fn remove_global_field(key: &str) {
+    if let Ok(mut fields) = GLOBAL_FIELDS.write() {
+        fields.retain(|(k, _)| k != key);
+    }
+}
+
+/// Returns the fields set with [`set_global_field`], in the order they were first set.
+pub fn global_fields() -> Vec<(String, String)> {
+    GLOBAL_FIELDS
+        .read()
+        .map(|fields| fields.clone())
+        .unwrap_or_default()
+}
+
 /// A guard that represents an active span.
 ///
 /// When the guard is dropped, the span is automatically removed from the context.
 /// This ensures proper cleanup even in the presence of early returns or panics.
+///
+/// Entering and exiting the span run the [`Hook::SpanEnter`](crate::Hook::SpanEnter)
+/// and [`Hook::SpanExit`](crate::Hook::SpanExit) hooks.
+///
+/// The guard can't be sent to another thread, as the span lives in the stack
+/// of the thread that entered it. If guards are dropped out of order,
+/// the spans entered after this one are removed along with it.
 pub struct SpanGuard {
-    _private: (),
+    /// Position of the span in the stack.
+    index: usize,
+    entered: Instant,
+    _not_send: PhantomData<*const ()>,
 }
 
 impl SpanGuard {
     /// Creates a new span guard and pushes the span onto the stack.
     pub fn new(span: Span) -> Self {
-        SPAN_STACK.with(|stack| {
-            stack.borrow_mut().push(span);
+        hooks::span_entered(&span);
+
+        let index = SPAN_STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            stack.push(span);
+            stack.len() - 1
         });
 
-        Self { _private: () }
+        Self {
+            index,
+            entered: Instant::now(),
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Sets a field of the span, replacing its value if the key already exists.
+    ///
+    /// The records logged from now on within the span include the field,
+    /// which is useful for values only known after the span is entered.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{info, span};
+    ///
+    /// let request = span!("request", "path" => "/login");
+    /// let user_id = authenticate()?;
+    ///
+    /// request.record("user_id", user_id);
+    /// info!("Authenticated");
+    /// // Logs: [INFO] Authenticated [request: path=/login, user_id=42]
+    /// ```
+    pub fn record(&self, key: impl Into<String>, value: impl ToString) {
+        let key = key.into();
+        let value = value.to_string();
+
+        SPAN_STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+
+            let Some(span) = stack.get_mut(self.index) else {
+                return;
+            };
+
+            match span.fields.iter_mut().find(|(k, _)| *k == key) {
+                Some(field) => field.1 = value,
+                None => span.fields.push((key, value)),
+            }
+        });
     }
 }
 
 impl Drop for SpanGuard {
     fn drop(&mut self) {
-        SPAN_STACK.with(|stack| {
-            stack.borrow_mut().pop();
+        let span = SPAN_STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+
+            if self.index >= stack.len() {
+                return None;
+            }
+
+            stack.drain(self.index..).next()
         });
+
+        if let Some(span) = span {
+            hooks::span_exited(&span, self.entered.elapsed());
+        }
     }
 }
 
-/// Returns the current span context as a vector of (span_name, key=value) pairs.
+/// Returns the current span context, one entry per active span.
 ///
-/// This function collects all active spans from the thread-local stack and
-/// formats them for inclusion in log records.
-pub fn current_context() -> Vec<(String, String)> {
-    SPAN_STACK.with(|stack| {
-        let stack = stack.borrow();
-        let mut context = Vec::new();
-
-        for span in stack.iter() {
-            for (key, value) in &span.fields {
-                context.push((span.name.clone(), format!("{}={}", key, value)));
-            }
-        }
-
-        context
-    })
+/// The spans are ordered from the outermost to the innermost,
+/// each one keeping all of its fields together.
+pub fn current_context() -> Vec<Span> {
+    SPAN_STACK.with(|stack| stack.borrow().clone())
 }
 
 /// Enters a new span with the given name and fields.
@@ -121,14 +249,14 @@ mod tests {
             let _span1 = enter("test", vec![("key1".to_string(), "value1".to_string())]);
             let ctx = current_context();
             assert_eq!(ctx.len(), 1);
-            assert_eq!(ctx[0].0, "test");
-            assert_eq!(ctx[0].1, "key1=value1");
+            assert_eq!(ctx[0].name, "test");
+            assert_eq!(ctx[0].fields[0], ("key1".to_string(), "value1".to_string()));
 
             {
                 let _span2 = enter("nested", vec![("key2".to_string(), "value2".to_string())]);
                 let ctx = current_context();
                 assert_eq!(ctx.len(), 2);
-                assert_eq!(ctx[1].0, "nested");
+                assert_eq!(ctx[1].name, "nested");
             }
 
             // After span2 drops
@@ -140,6 +268,40 @@ mod tests {
         assert_eq!(current_context().len(), 0);
     }
 
+    #[test]
+    fn test_out_of_order_drop() {
+        let outer = enter("outer", Vec::new());
+        let inner = enter("inner", Vec::new());
+
+        drop(outer);
+        assert!(current_context().is_empty());
+
+        let next = enter("next", Vec::new());
+        drop(inner);
+
+        assert_eq!(current_context(), [Span::new("next", Vec::new())]);
+        drop(next);
+    }
+
+    #[test]
+    fn test_record_field() {
+        let outer = enter("request", vec![("path".to_string(), "/".to_string())]);
+        let _inner = enter("db", Vec::new());
+
+        outer.record("user_id", 42);
+        outer.record("path", "/login");
+
+        let ctx = current_context();
+        assert_eq!(
+            ctx[0].fields,
+            [
+                ("path".to_string(), "/login".to_string()),
+                ("user_id".to_string(), "42".to_string())
+            ]
+        );
+        assert!(ctx[1].fields.is_empty());
+    }
+
     #[test]
     fn test_multiple_fields() {
         let _span = enter(
@@ -150,10 +312,13 @@ mod tests {
             ],
         );
 
+        // A span with several fields is a single context entry
         let ctx = current_context();
-        assert_eq!(ctx.len(), 2);
-        assert_eq!(ctx[0].1, "field1=value1");
-        assert_eq!(ctx[1].1, "field2=value2");
+        assert_eq!(ctx.len(), 1);
+        assert_eq!(ctx[0].fields.len(), 2);
+        assert_eq!(ctx[0].fields[1].1, "value2");
+        assert_eq!(ctx[0].get("field2"), Some("value2"));
+        assert_eq!(ctx[0].get("field3"), None);
     }
 
     #[test]
@@ -3,8 +3,12 @@
 //! Provides utilities for adding color to log messages in terminal output.
 //! These colors are automatically stripped when writing to non-terminal
 //! targets like files.
+//!
+//! Coloring is enabled by the default `color` feature. When it is disabled,
+//! the `Colorize` and `Style` methods and [`gradient`] return the text unchanged.
 
-use std::fmt::Display;
+use crate::util;
+use std::{borrow::Cow, env, fmt::Display, sync::OnceLock};
 
 /// Terminal colors for text output.
 ///
@@ -117,9 +121,382 @@ impl Color {
     }
 }
 
+impl Color {
+    /// Returns the red, green and blue components of the color.
+    ///
+    /// Named colors use the xterm palette values, `Default` is treated as white.
+    fn rgb(&self) -> (u8, u8, u8) {
+        const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+        match *self {
+            Color::Black => (0, 0, 0),
+            Color::Red => (205, 0, 0),
+            Color::Green => (0, 205, 0),
+            Color::Yellow => (205, 205, 0),
+            Color::Blue => (0, 0, 238),
+            Color::Magenta => (205, 0, 205),
+            Color::Cyan => (0, 205, 205),
+            Color::White | Color::Default => (229, 229, 229),
+            Color::BrightBlack => (127, 127, 127),
+            Color::BrightRed => (255, 0, 0),
+            Color::BrightGreen => (0, 255, 0),
+            Color::BrightYellow => (255, 255, 0),
+            Color::BrightBlue => (92, 92, 255),
+            Color::BrightMagenta => (255, 0, 255),
+            Color::BrightCyan => (0, 255, 255),
+            Color::BrightWhite => (255, 255, 255),
+            Color::ID(id @ 0..=15) => NAMED[id as usize].rgb(),
+            Color::ID(id @ 16..=231) => {
+                let id = id - 16;
+                (
+                    LEVELS[(id / 36) as usize],
+                    LEVELS[(id / 6 % 6) as usize],
+                    LEVELS[(id % 6) as usize],
+                )
+            }
+            Color::ID(id) => {
+                let gray = 8 + (id - 232) * 10;
+                (gray, gray, gray)
+            }
+            Color::RGB(r, g, b) => (r, g, b),
+        }
+    }
+
+    /// Returns the closest color of the 256 palette.
+    fn to_ansi256(self) -> u8 {
+        const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+        let (r, g, b) = match self {
+            Color::ID(id) => return id,
+            Color::RGB(r, g, b) => (r, g, b),
+            named => {
+                return NAMED.iter().position(|color| *color == named).unwrap_or(7) as u8;
+            }
+        };
+
+        if r == g && g == b {
+            return match r {
+                0..8 => 16,
+                249.. => 231,
+                _ => 232 + (r - 8) / 10,
+            };
+        }
+
+        let level = |v: u8| {
+            LEVELS
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, level)| level.abs_diff(v))
+                .map_or(0, |(i, _)| i as u8)
+        };
+
+        16 + 36 * level(r) + 6 * level(g) + level(b)
+    }
+
+    /// Returns the closest of the 16 named colors.
+    fn to_ansi16(self) -> Color {
+        if let Color::ID(id @ 0..=15) = self {
+            return NAMED[id as usize];
+        }
+
+        let (r, g, b) = self.rgb();
+        let distance = |color: &&Color| {
+            let (cr, cg, cb) = color.rgb();
+            [(r, cr), (g, cg), (b, cb)]
+                .iter()
+                .map(|(a, b)| (a.abs_diff(*b) as u32).pow(2))
+                .sum::<u32>()
+        };
+
+        NAMED.iter().min_by_key(distance).copied().unwrap_or(self)
+    }
+}
+
+/// Converts a color to its index in the 256 color palette.
+///
+/// Named colors map to the first 16 entries, `Default` to white (7),
+/// and RGB colors to the closest entry of the palette.
+impl From<Color> for u8 {
+    fn from(color: Color) -> Self {
+        color.to_ansi256()
+    }
+}
+
+/// Converts an index of the 256 color palette to a color.
+///
+/// The first 16 entries are the named colors, the others are `Color::ID`,
+/// so converting the result back gives the same index.
+/// Every index is valid, so `TryFrom<u8>` never fails.
+impl From<u8> for Color {
+    fn from(id: u8) -> Self {
+        match NAMED.get(id as usize) {
+            Some(named) => *named,
+            None => Color::ID(id),
+        }
+    }
+}
+
+/// The 16 named colors, in palette order.
+const NAMED: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::BrightBlack,
+    Color::BrightRed,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+
+/// Controls whether and how a target emits colors.
+///
+/// See [`Console::color_mode`](crate::Console::color_mode).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorMode {
+    /// Colors only when writing to a terminal, unless the `NO_COLOR` environment variable
+    /// is set or `TERM` is `dumb`. The color depth is detected from `COLORTERM`
+    /// (`truecolor` or `24bit`) and `TERM` (e.g. `xterm-256color`),
+    /// otherwise colors are written as they are.
+    #[default]
+    Auto,
+
+    /// Always writes colors as they are.
+    Always,
+
+    /// Never writes colors, ANSI codes are stripped.
+    Never,
+
+    /// Always writes colors, converting them to the 16 named colors.
+    Ansi16,
+
+    /// Always writes colors, converting RGB colors to the 256 palette.
+    Ansi256,
+
+    /// Always writes colors, including RGB ones. Same as `Always`.
+    Truecolor,
+}
+
+impl ColorMode {
+    /// Resolves `Auto` to the mode to use, other modes are returned unchanged.
+    fn resolve(self, is_terminal: bool) -> ColorMode {
+        static DETECTED: OnceLock<ColorMode> = OnceLock::new();
+
+        match self {
+            ColorMode::Auto if !is_terminal => ColorMode::Never,
+            ColorMode::Auto => *DETECTED.get_or_init(|| {
+                detect(
+                    env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
+                    env::var("COLORTERM").ok().as_deref(),
+                    env::var("TERM").ok().as_deref(),
+                )
+            }),
+            mode => mode,
+        }
+    }
+
+    /// Applies the mode to a string, stripping or converting its colors if needed.
+    pub(crate) fn apply(self, s: &str, is_terminal: bool) -> Cow<'_, str> {
+        if !s.contains('\x1b') {
+            return Cow::Borrowed(s);
+        }
+
+        match self.resolve(is_terminal) {
+            ColorMode::Never => util::strip_ansi_codes_cow(s),
+            mode @ (ColorMode::Ansi16 | ColorMode::Ansi256) => Cow::Owned(downgrade(s, mode)),
+            _ => Cow::Borrowed(s),
+        }
+    }
+}
+
+/// Detects the color mode from the environment of a terminal.
+fn detect(no_color: bool, colorterm: Option<&str>, term: Option<&str>) -> ColorMode {
+    if no_color || term == Some("dumb") {
+        ColorMode::Never
+    } else if matches!(colorterm, Some("truecolor" | "24bit")) {
+        ColorMode::Truecolor
+    } else if term.is_some_and(|term| term.contains("256color")) {
+        ColorMode::Ansi256
+    } else {
+        ColorMode::Always
+    }
+}
+
+/// Converts the 256 palette and RGB colors of a string to the depth of `mode`,
+/// either `Ansi16` or `Ansi256`.
+fn downgrade(s: &str, mode: ColorMode) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find("\x1b[") {
+        let Some(len) = rest[start..].find('m') else {
+            break;
+        };
+
+        let params = &rest[start + 2..start + len];
+
+        out.push_str(&rest[..start]);
+        out.push_str("\x1b[");
+        out.push_str(&downgrade_params(params, mode));
+        out.push('m');
+
+        rest = &rest[start + len + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Converts the extended colors of a list of SGR parameters, e.g. `1;38;2;255;0;0`.
+fn downgrade_params(params: &str, mode: ColorMode) -> String {
+    let parts = params.split(';').collect::<Vec<_>>();
+    let mut out = Vec::with_capacity(parts.len());
+    let mut i = 0;
+
+    while i < parts.len() {
+        let part = parts[i];
+        let number = |offset: usize| parts.get(i + offset).and_then(|n| n.parse::<u8>().ok());
+
+        let extended = match (part, parts.get(i + 1)) {
+            ("38" | "48", Some(&"5")) => number(2).map(|id| (Color::ID(id), 3)),
+            ("38" | "48", Some(&"2")) => match (number(2), number(3), number(4)) {
+                (Some(r), Some(g), Some(b)) => Some((Color::RGB(r, g, b), 5)),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let Some((color, len)) = extended else {
+            out.push(part.to_string());
+            i += 1;
+            continue;
+        };
+
+        let background = part == "48";
+
+        out.push(match mode {
+            ColorMode::Ansi256 => format!("{};5;{}", part, color.to_ansi256()),
+            _ => {
+                let color = color.to_ansi16();
+                let code = match background {
+                    true => color.ansi_code_background(),
+                    false => color.ansi_code_foreground(),
+                };
+
+                code.trim_start_matches("\x1b[")
+                    .trim_end_matches('m')
+                    .to_string()
+            }
+        });
+
+        i += len;
+    }
+
+    out.join(";")
+}
+
+/// Colors each character of a string with a gradient between two colors.
+///
+/// The color is interpolated linearly from `from` (first character) to `to` (last character),
+/// and every character gets its own RGB escape code. Whitespace is left uncolored.
+///
+/// Named and 256-palette colors are converted to their RGB values.
+///
+/// # Examples
+///
+/// ```
+/// use traccia::{Color, gradient};
+///
+/// let banner = gradient("STARTING", Color::RGB(255, 0, 0), Color::RGB(0, 0, 255));
+///
+/// # #[cfg(feature = "color")]
+/// # {
+/// assert!(banner.starts_with("\x1b[38;2;255;0;0mS"));
+/// assert!(banner.ends_with("\x1b[38;2;0;0;255mG\x1b[39m"));
+/// # }
+/// ```
+pub fn gradient(s: &str, from: Color, to: Color) -> String {
+    let count = s.chars().count();
+
+    if count == 0 || !cfg!(feature = "color") {
+        return s.to_string();
+    }
+
+    let (from, to) = (from.rgb(), to.rgb());
+    let steps = (count - 1).max(1) as f32;
+    let lerp = |a: u8, b: u8, t: f32| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+    let mut out = String::with_capacity(s.len() * 20);
+
+    for (i, c) in s.chars().enumerate() {
+        if c.is_whitespace() {
+            out.push(c);
+            continue;
+        }
+
+        let t = i as f32 / steps;
+        let color = Color::RGB(
+            lerp(from.0, to.0, t),
+            lerp(from.1, to.1, t),
+            lerp(from.2, to.2, t),
+        );
+
+        out.push_str(&color.ansi_code_foreground());
+        out.push(c);
+    }
+
+    out.push_str("\x1b[39m");
+    out
+}
+
+/// Wraps a string between an opening and a closing escape code.
+///
+/// The closing codes only reset what the opening code set
+/// (e.g. `\x1b[39m` for the foreground color), so styles can be combined.
+/// When the string already contains the same reset, or a full `\x1b[0m` reset,
+/// the opening code is applied again right after it, so an inner styled substring
+/// doesn't cut the enclosing style short.
+///
+/// Without the `color` feature, the string is returned unchanged.
+fn wrap(s: &str, open: &str, close: &str) -> String {
+    if !cfg!(feature = "color") {
+        return s.to_string();
+    }
+
+    let inner = s
+        .replace(close, &format!("{}{}", close, open))
+        .replace("\x1b[0m", &format!("\x1b[0m{}", open));
+
+    format!("{}{}{}", open, inner, close)
+}
+
 /// Trait for applying colors to strings.
 ///
 /// This trait provides the ability to color text using ANSI escape codes.
+///
+/// Colors can be nested: after a colored substring ends,
+/// the enclosing color is restored.
+///
+/// # Examples
+///
+/// ```
+/// use traccia::{Color, Colorize};
+///
+/// let message = format!("request {} failed", "42".color(Color::Cyan));
+///
+/// # #[cfg(feature = "color")]
+/// assert_eq!(
+///     message.color(Color::Red),
+///     "\x1b[31mrequest \x1b[36m42\x1b[39m\x1b[31m failed\x1b[39m"
+/// );
+/// ```
 pub trait Colorize: Display {
     /// Applies a color to the string.
     ///
@@ -131,7 +508,7 @@ pub trait Colorize: Display {
     ///
     /// A new string with the color applied via ANSI escape codes
     fn color(&self, color: Color) -> String {
-        format!("{}{}\x1b[0m", color.ansi_code_foreground(), self)
+        wrap(&self.to_string(), &color.ansi_code_foreground(), "\x1b[39m")
     }
 
     /// Applies a background color to the string.
@@ -144,54 +521,156 @@ pub trait Colorize: Display {
     ///
     /// A new string with the background color applied via ANSI escape codes
     fn background(&self, color: Color) -> String {
-        format!("{}{}\x1b[0m", color.ansi_code_background(), self)
+        wrap(&self.to_string(), &color.ansi_code_background(), "\x1b[49m")
     }
 }
 
 impl Colorize for str {}
 impl Colorize for String {}
 
+/// Trait for applying text styles to strings.
+///
+/// Like colors, styles only reset the attribute they set,
+/// so they can be combined with colors and with each other.
 pub trait Style: Display {
     /// Applies bold style to the string.
     fn bold(&self) -> String {
-        format!("\x1b[1m{}\x1b[0m", self)
+        wrap(&self.to_string(), "\x1b[1m", "\x1b[22m")
     }
 
     /// Dims the color of the string.
     fn dim(&self) -> String {
-        format!("\x1b[2m{}\x1b[0m", self)
+        wrap(&self.to_string(), "\x1b[2m", "\x1b[22m")
     }
 
     /// Applies italic style to the string.
     fn italic(&self) -> String {
-        format!("\x1b[3m{}\x1b[0m", self)
+        wrap(&self.to_string(), "\x1b[3m", "\x1b[23m")
     }
 
     /// Underlines the string.
     fn underline(&self) -> String {
-        format!("\x1b[4m{}\x1b[0m", self)
+        wrap(&self.to_string(), "\x1b[4m", "\x1b[24m")
     }
 
     /// Makes the content of the string blink.
     fn blink(&self) -> String {
-        format!("\x1b[5m{}\x1b[0m", self)
+        wrap(&self.to_string(), "\x1b[5m", "\x1b[25m")
     }
 
     /// Reverses the foreground and background colors of the string.
     fn reverse(&self) -> String {
-        format!("\x1b[7m{}\x1b[0m", self)
+        wrap(&self.to_string(), "\x1b[7m", "\x1b[27m")
     }
 
     /// Hides the content of the string with the background color.
     fn hidden(&self) -> String {
-        format!("\x1b[8m{}\x1b[0m", self)
+        wrap(&self.to_string(), "\x1b[8m", "\x1b[28m")
     }
 
     /// Strikes through the content of the string.
     fn striketrough(&self) -> String {
-        format!("\x1b[9m{}\x1b[0m", self)
+        wrap(&self.to_string(), "\x1b[9m", "\x1b[29m")
     }
 }
 
 impl Style for str {}
 impl Style for String {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn test_combined_styles_use_specific_resets() {
+        assert_eq!(
+            "x".bold().color(Color::Red),
+            "\x1b[31m\x1b[1mx\x1b[22m\x1b[39m"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn test_nested_colors_restore_outer_color() {
+        let inner = "b".color(Color::Blue);
+        let outer = format!("a{}c", inner).color(Color::Green);
+
+        assert_eq!(outer, "\x1b[32ma\x1b[34mb\x1b[39m\x1b[32mc\x1b[39m");
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn test_gradient() {
+        assert_eq!(gradient("", Color::Red, Color::Blue), "");
+
+        assert_eq!(
+            gradient("a c", Color::RGB(0, 0, 0), Color::RGB(200, 100, 0)),
+            "\x1b[38;2;0;0;0ma \x1b[38;2;200;100;0mc\x1b[39m"
+        );
+
+        assert_eq!(
+            gradient("x", Color::ID(196), Color::Blue),
+            "\x1b[38;2;255;0;0mx\x1b[39m"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn test_full_reset_restores_outer_style() {
+        let outer = "a\x1b[0mb".bold();
+
+        assert_eq!(outer, "\x1b[1ma\x1b[0m\x1b[1mb\x1b[22m");
+    }
+
+    #[test]
+    fn test_downgrade_colors() {
+        let s = "\x1b[1;38;2;255;0;0mred\x1b[39m \x1b[48;5;21mblue\x1b[49m";
+
+        assert_eq!(
+            downgrade(s, ColorMode::Ansi256),
+            "\x1b[1;38;5;196mred\x1b[39m \x1b[48;5;21mblue\x1b[49m"
+        );
+        assert_eq!(
+            downgrade(s, ColorMode::Ansi16),
+            "\x1b[1;91mred\x1b[39m \x1b[44mblue\x1b[49m"
+        );
+        assert_eq!(Color::RGB(128, 128, 128).to_ansi256(), 244);
+    }
+
+    #[test]
+    fn test_color_u8_round_trip() {
+        for id in 0..=u8::MAX {
+            assert_eq!(u8::from(Color::from(id)), id);
+        }
+
+        assert_eq!(Color::from(9), Color::BrightRed);
+        assert_eq!(Color::from(200), Color::ID(200));
+        assert_eq!(u8::from(Color::Default), 7);
+        assert_eq!(u8::from(Color::RGB(255, 0, 0)), 196);
+    }
+
+    #[test]
+    fn test_color_mode() {
+        assert_eq!(detect(true, Some("truecolor"), None), ColorMode::Never);
+        assert_eq!(detect(false, Some("24bit"), None), ColorMode::Truecolor);
+        assert_eq!(
+            detect(false, None, Some("xterm-256color")),
+            ColorMode::Ansi256
+        );
+        assert_eq!(detect(false, None, Some("xterm")), ColorMode::Always);
+
+        assert_eq!(ColorMode::Auto.apply("\x1b[31mx\x1b[39m", false), "x");
+        assert_eq!(
+            ColorMode::Always.apply("\x1b[31mx\x1b[39m", false),
+            "\x1b[31mx\x1b[39m"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "color"))]
+    fn test_without_color_feature() {
+        assert_eq!("x".bold().color(Color::Red), "x");
+        assert_eq!(gradient("abc", Color::Red, Color::Blue), "abc");
+    }
+}
@@ -0,0 +1,85 @@
+//! Per-thread suppression of logging.
+//!
+//! Unlike a level override, suppression mutes every record logged
+//! on the current thread, whatever its level, e.g. in a noisy loop.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use traccia::suppress;
+//!
+//! let result = suppress(|| {
+//!     // Nothing is logged here
+//!     run_benchmark()
+//! });
+//! ```
+
+use std::{cell::Cell, marker::PhantomData};
+
+thread_local! {
+    /// Whether logging is suppressed on this thread.
+    static SUPPRESSED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A guard that represents an active suppression.
+///
+/// When the guard is dropped, the previous state of the thread is restored.
+/// This ensures proper cleanup even in the presence of early returns or panics.
+///
+/// The guard can't be sent to another thread, as the suppression belongs to the thread that set it.
+#[must_use = "logging is restored as soon as the guard is dropped"]
+pub struct SuppressGuard {
+    previous: bool,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for SuppressGuard {
+    fn drop(&mut self) {
+        SUPPRESSED.set(self.previous);
+    }
+}
+
+/// Drops every record logged on the current thread until the returned guard is dropped.
+pub fn suppress_logging() -> SuppressGuard {
+    SuppressGuard {
+        previous: SUPPRESSED.replace(true),
+        _not_send: PhantomData,
+    }
+}
+
+/// Runs `f` without logging anything on the current thread.
+pub fn suppress<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let _guard = suppress_logging();
+    f()
+}
+
+/// Returns `true` if logging is suppressed on the current thread.
+pub fn is_suppressed() -> bool {
+    SUPPRESSED.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suppression_restored() {
+        assert!(!is_suppressed());
+
+        suppress(|| {
+            let guard = suppress_logging();
+            drop(guard);
+
+            assert!(is_suppressed());
+        });
+
+        let _ = std::panic::catch_unwind(|| {
+            suppress(|| panic!("boom"));
+        });
+
+        assert!(!is_suppressed());
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{LogLevel, Record, Target, TargetId, error::Error};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+    mpsc,
+};
+
+/// Item that a [`ChannelTarget`] can send.
+///
+/// Implemented for [`Record`], sending the record itself, and [`String`],
+/// sending the formatted message.
+pub trait ChannelItem: Send + 'static {
+    /// Creates the item from a record and its formatted message.
+    fn from_record(record: &Record, formatted: &str) -> Self;
+}
+
+impl ChannelItem for Record {
+    fn from_record(record: &Record, _: &str) -> Self {
+        record.clone()
+    }
+}
+
+impl ChannelItem for String {
+    fn from_record(_: &Record, formatted: &str) -> Self {
+        formatted.to_string()
+    }
+}
+
+enum ChannelSender<T> {
+    Unbounded(mpsc::Sender<T>),
+    Bounded(mpsc::SyncSender<T>),
+}
+
+// Derived `Clone` would require `T: Clone`
+impl<T> Clone for ChannelSender<T> {
+    fn clone(&self) -> Self {
+        match self {
+            ChannelSender::Unbounded(sender) => ChannelSender::Unbounded(sender.clone()),
+            ChannelSender::Bounded(sender) => ChannelSender::Bounded(sender.clone()),
+        }
+    }
+}
+
+/// Target that forwards records to a channel, to be processed elsewhere.
+///
+/// By default the records themselves are sent, so the receiving side gets the level,
+/// the message, the location and the span context. Use `ChannelTarget<String>`
+/// to send the formatted messages instead.
+///
+/// The target never blocks the logger: items are dropped when a bounded channel
+/// is full or the receiver has been dropped. Clones share the same counter,
+/// so keeping a clone of the target allows reading [`ChannelTarget::dropped`] later.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use std::sync::mpsc;
+/// use traccia::{ChannelTarget, Config};
+///
+/// let (sender, receiver) = mpsc::sync_channel(1024);
+///
+/// let config = Config {
+///     targets: vec![Box::new(ChannelTarget::bounded(sender))],
+///     ..Config::default()
+/// };
+///
+/// std::thread::spawn(move || {
+///     for record in receiver {
+///         // Route or process the record
+///     }
+/// });
+/// ```
+pub struct ChannelTarget<T = Record> {
+    sender: ChannelSender<T>,
+    level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl<T> Clone for ChannelTarget<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            level: self.level,
+            only_levels: self.only_levels.clone(),
+            dropped: Arc::clone(&self.dropped),
+        }
+    }
+}
+
+impl<T> ChannelTarget<T>
+where
+    T: ChannelItem,
+{
+    /// Creates a target sending to an unbounded channel.
+    pub fn new(sender: mpsc::Sender<T>) -> Self {
+        Self::with_sender(ChannelSender::Unbounded(sender))
+    }
+
+    /// Creates a target sending to a bounded channel.
+    ///
+    /// Items are dropped while the channel is full.
+    pub fn bounded(sender: mpsc::SyncSender<T>) -> Self {
+        Self::with_sender(ChannelSender::Bounded(sender))
+    }
+
+    fn with_sender(sender: ChannelSender<T>) -> Self {
+        Self {
+            sender,
+            level: None,
+            only_levels: None,
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`](crate::File::only_level).
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+
+    /// Returns how many items have been dropped,
+    /// because the channel was full or disconnected.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn send(&self, item: T) {
+        let sent = match &self.sender {
+            ChannelSender::Unbounded(sender) => sender.send(item).is_ok(),
+            ChannelSender::Bounded(sender) => sender.try_send(item).is_ok(),
+        };
+
+        if !sent {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<T> Target for ChannelTarget<T>
+where
+    T: ChannelItem,
+{
+    /// Sends a record made of the level and the message.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        let record = Record::builder(level, formatted).build();
+        self.send(T::from_record(&record, formatted));
+        Ok(())
+    }
+
+    /// Sends the record, or its formatted message.
+    fn write_record(&self, record: &Record, formatted: &str) -> Result<(), Error> {
+        self.send(T::from_record(record, formatted));
+        Ok(())
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    fn id(&self) -> TargetId {
+        TargetId::Custom("channel".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_channel_drops_when_full() {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let target = ChannelTarget::bounded(sender);
+
+        let record = Record::builder(LogLevel::Warn, "first").build();
+        target.write_record(&record, "[WARN] first").unwrap();
+        target.write(LogLevel::Info, "second").unwrap();
+
+        let received: Record = receiver.try_recv().unwrap();
+
+        assert_eq!(received.message, "first");
+        assert_eq!(received.level, LogLevel::Warn);
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(target.clone().dropped(), 1);
+    }
+
+    #[test]
+    fn test_formatted_messages() {
+        let (sender, receiver) = mpsc::channel::<String>();
+        let target = ChannelTarget::new(sender);
+
+        let record = Record::builder(LogLevel::Info, "ready").build();
+        target.write_record(&record, "[INFO] ready").unwrap();
+
+        drop(receiver);
+        target.write_record(&record, "[INFO] ready").unwrap();
+
+        assert_eq!(target.dropped(), 1);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{LogLevel, Target, TargetId, error::Error};
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    process::{Command, Stdio},
+    sync::{Arc, Mutex},
+};
+
+/// Number of lines kept by [`ClipboardTarget::new`].
+const DEFAULT_CAPACITY: usize = 100;
+
+/// Target that keeps the most recent lines, to copy them to the system clipboard.
+///
+/// Lines are stored in a ring buffer, dropping the oldest ones
+/// once it is full. They are copied, joined by newlines, when [`ClipboardTarget::copy`]
+/// is called, or automatically when a record at or above the level set with
+/// [`ClipboardTarget::copy_on`] is written, e.g. to have the context of the last error
+/// ready to paste in a bug report.
+///
+/// The text is handed to the clipboard tool of the platform: `pbcopy` on macOS,
+/// `clip` on Windows, and `wl-copy`, `xclip` or `xsel` on other systems, whichever
+/// is installed. Use [`ClipboardTarget::command`] to pick another one.
+///
+/// Only available with the `clipboard` feature.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{ClipboardTarget, Config, Console, LogLevel};
+///
+/// // Copies the last 20 lines every time an error is logged
+/// let clipboard = ClipboardTarget::with_capacity(20).copy_on(LogLevel::Error);
+///
+/// traccia::init_with_config(
+///     Config::default()
+///         .with_target(Console::new())
+///         .with_target(clipboard.clone()),
+/// );
+///
+/// // ...or on demand, e.g. from a key binding
+/// clipboard.copy().expect("Failed to copy the logs");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClipboardTarget {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+    copy_on: Option<LogLevel>,
+    command: Option<(String, Vec<String>)>,
+    level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+}
+
+impl Default for ClipboardTarget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClipboardTarget {
+    /// Creates a target keeping the last 100 lines.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    /// Creates a target keeping the last `capacity` lines.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+            copy_on: None,
+            command: None,
+            level: None,
+            only_levels: None,
+        }
+    }
+
+    /// Builder method to copy the lines every time a record
+    /// at or above `level` is written.
+    pub fn copy_on(mut self, level: LogLevel) -> Self {
+        self.copy_on = Some(level);
+        self
+    }
+
+    /// Builder method to set the command the text is piped to,
+    /// instead of the clipboard tool of the platform.
+    ///
+    /// (e.g. `command("xclip", ["-selection", "primary"])` to copy to the primary selection)
+    pub fn command<I, S>(mut self, program: impl Into<String>, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.command = Some((program.into(), args.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`](crate::File::only_level).
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+
+    /// Returns the lines currently kept, from the oldest to the newest.
+    pub fn lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Removes all the lines kept so far.
+    pub fn clear(&self) {
+        if let Ok(mut lines) = self.lines.lock() {
+            lines.clear();
+        }
+    }
+
+    /// Copies the lines kept so far to the clipboard, joined by newlines.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` once the clipboard tool has exited successfully,
+    /// or an error if no tool could be run or it failed
+    pub fn copy(&self) -> Result<(), Error> {
+        let text = self.lines().join("\n");
+
+        match &self.command {
+            Some((program, args)) => pipe(program, args, &text),
+            None => copy_with_platform_tool(&text),
+        }
+    }
+}
+
+/// Runs `program` with `args`, writing `text` to its stdin.
+fn pipe<S: AsRef<str>>(program: &str, args: &[S], text: &str) -> Result<(), Error> {
+    let mut child = Command::new(program)
+        .args(args.iter().map(AsRef::as_ref))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let status = child.wait()?;
+
+    if !status.success() {
+        return Err(io::Error::other(format!("{} exited with {}", program, status)).into());
+    }
+
+    Ok(())
+}
+
+/// Copies `text` with the first clipboard tool of the platform that is installed.
+fn copy_with_platform_tool(text: &str) -> Result<(), Error> {
+    let tools: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else if cfg!(windows) {
+        &[("clip", &[])]
+    } else if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    } else {
+        &[
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    };
+
+    for (program, args) in tools {
+        match pipe(program, args, text) {
+            Err(Error::Io(e)) if e.kind() == io::ErrorKind::NotFound => continue,
+            result => return result,
+        }
+    }
+
+    Err(io::Error::new(io::ErrorKind::NotFound, "no clipboard tool is installed").into())
+}
+
+impl Target for ClipboardTarget {
+    /// Keeps the message and copies the lines
+    /// if its level reaches the one set with `copy_on`.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        if self.capacity > 0
+            && let Ok(mut lines) = self.lines.lock()
+        {
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+
+            lines.push_back(formatted.to_string());
+        }
+
+        if self.copy_on.is_some_and(|copy_on| copy_on.includes(level)) {
+            self.copy()?;
+        }
+
+        Ok(())
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    /// Returns `false`, the clipboard gets plain text.
+    fn wants_color(&self) -> bool {
+        false
+    }
+
+    fn id(&self) -> TargetId {
+        TargetId::Custom("clipboard".to_string())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_copy_on_level() {
+        let path = std::env::temp_dir().join(format!("traccia-clipboard-{}", std::process::id()));
+
+        let clipboard = ClipboardTarget::with_capacity(2)
+            .copy_on(LogLevel::Error)
+            .command("sh", ["-c", &format!("cat > {}", path.display())]);
+
+        clipboard.write(LogLevel::Info, "first").unwrap();
+        clipboard.write(LogLevel::Info, "second").unwrap();
+        assert!(!path.exists());
+
+        clipboard.write(LogLevel::Error, "failed").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second\nfailed");
+
+        clipboard.clear();
+        clipboard.copy().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{LogLevel, Record, Target, TargetId, error::Error, format_span_context};
+
+/// Target wrapper that adds a static prefix and/or suffix to every formatted message.
+///
+/// Useful to tag the lines of a shared log file, e.g. with the name of the service,
+/// without writing a whole formatter for it. The prefix is placed at the start
+/// of the message, or after the span context when spans are rendered at the start
+/// (see [`SpanPosition::Start`](crate::SpanPosition::Start)), so the context still
+/// leads the line: `[request: id=1] [auth-service] [INFO] Logged in`.
+///
+/// The span context is recognized when it is rendered with the default [`SpanFormat`](crate::SpanFormat),
+/// and when the record is available, which is not the case for [`Target::write`] and
+/// [`Target::write_bytes`]. Otherwise the prefix is placed at the very start.
+///
+/// The prefix and suffix are added before the wrapped target processes the message,
+/// so a `File` target strips any ANSI codes from them as well.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, Decorate, File, FileMode};
+///
+/// let file = File::new("logs/services.log", FileMode::Append).expect("Failed to open file");
+///
+/// let config = Config {
+///     targets: vec![Box::new(Decorate::new(file).prefix("[auth-service] "))],
+///     ..Config::default()
+/// };
+/// ```
+#[derive(Clone)]
+pub struct Decorate {
+    inner: Box<dyn Target>,
+    prefix: String,
+    suffix: String,
+}
+
+impl Decorate {
+    /// Wraps `inner`, without prefix or suffix.
+    pub fn new<T>(inner: T) -> Self
+    where
+        T: Target + 'static,
+    {
+        Self {
+            inner: Box::new(inner),
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+
+    /// Builder method to set the text prepended to every message.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Builder method to set the text appended to every message.
+    pub fn suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+
+    fn decorate(&self, formatted: &str) -> String {
+        format!("{}{}{}", self.prefix, formatted, self.suffix)
+    }
+
+    /// Decorates the message of a record, placing the prefix after the leading span context.
+    fn decorate_record(&self, record: &Record, formatted: &str) -> String {
+        match leading_context_end(formatted, &format_span_context(&record.context)) {
+            Some(end) => format!(
+                "{}{}{}{}",
+                &formatted[..end],
+                self.prefix,
+                &formatted[end..],
+                self.suffix
+            ),
+            None => self.decorate(formatted),
+        }
+    }
+}
+
+/// Returns the position right after `context` and the whitespace following it,
+/// if the message starts with it, ignoring ANSI codes.
+fn leading_context_end(formatted: &str, context: &str) -> Option<usize> {
+    if context.is_empty() {
+        return None;
+    }
+
+    let mut end = 0;
+
+    for c in context.chars() {
+        // Skip the escape sequences, e.g. of a full line color
+        while formatted[end..].starts_with("\x1b[") {
+            end += formatted[end..].find('m')? + 1;
+        }
+
+        if !formatted[end..].starts_with(c) {
+            return None;
+        }
+
+        end += c.len_utf8();
+    }
+
+    let rest = &formatted[end..];
+
+    Some(end + rest.len() - rest.trim_start_matches(' ').len())
+}
+
+impl Target for Decorate {
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        self.inner.write(level, &self.decorate(formatted))
+    }
+
+    fn write_bytes(&self, level: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        let mut decorated = Vec::with_capacity(self.prefix.len() + bytes.len() + self.suffix.len());
+
+        decorated.extend_from_slice(self.prefix.as_bytes());
+        decorated.extend_from_slice(bytes);
+        decorated.extend_from_slice(self.suffix.as_bytes());
+
+        self.inner.write_bytes(level, &decorated)
+    }
+
+    fn write_record(&self, record: &Record, formatted: &str) -> Result<(), Error> {
+        self.inner
+            .write_record(record, &self.decorate_record(record, formatted))
+    }
+
+    fn write_batch(&self, batch: &[(&Record, &str)]) -> Result<(), Error> {
+        let decorated = batch
+            .iter()
+            .map(|(record, formatted)| self.decorate_record(record, formatted))
+            .collect::<Vec<_>>();
+
+        let batch = batch
+            .iter()
+            .zip(&decorated)
+            .map(|((record, _), formatted)| (*record, formatted.as_str()))
+            .collect::<Vec<_>>();
+
+        self.inner.write_batch(&batch)
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        self.inner.flush()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.inner.validate()
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.inner.filter_level()
+    }
+
+    fn max_level(&self) -> Option<LogLevel> {
+        self.inner.max_level()
+    }
+
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.inner.only_levels()
+    }
+
+    fn wants_color(&self) -> bool {
+        self.inner.wants_color()
+    }
+
+    fn id(&self) -> TargetId {
+        self.inner.id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemoryTarget, Span};
+
+    #[test]
+    fn test_decorate() {
+        let memory = MemoryTarget::new();
+        let decorated = Decorate::new(memory.clone()).prefix("[auth] ").suffix(" ;");
+
+        let record = Record::builder(LogLevel::Info, "login").build();
+
+        decorated.write(LogLevel::Info, "started").unwrap();
+        decorated
+            .write_batch(&[(&record, "login"), (&record, "logout")])
+            .unwrap();
+
+        assert_eq!(
+            memory
+                .drain()
+                .into_iter()
+                .map(|(_, message)| message)
+                .collect::<Vec<_>>(),
+            ["[auth] started ;", "[auth] login ;", "[auth] logout ;"]
+        );
+    }
+
+    #[test]
+    fn test_prefix_after_leading_spans() {
+        use crate::{DefaultFormatter, Formatter, SpanPosition, util};
+
+        let memory = MemoryTarget::new();
+        let decorated = Decorate::new(memory.clone()).prefix("[auth] ");
+
+        let record = Record::builder(LogLevel::Info, "login")
+            .context(vec![Span::new(
+                "request",
+                vec![("id".to_string(), "1".to_string())],
+            )])
+            .build();
+
+        for formatter in [
+            DefaultFormatter::with_position(SpanPosition::Start),
+            DefaultFormatter::with_position(SpanPosition::Start).with_full_line_color(),
+            DefaultFormatter::with_position(SpanPosition::End),
+        ] {
+            decorated
+                .write_record(&record, &formatter.format(&record))
+                .unwrap();
+        }
+
+        assert_eq!(
+            memory
+                .drain()
+                .into_iter()
+                .map(|(_, message)| util::strip_ansi_codes(&message))
+                .collect::<Vec<_>>(),
+            [
+                "[request: id=1] [auth] [INFO] login",
+                "[request: id=1] [auth] [INFO] login",
+                "[auth] [INFO] login [request: id=1]"
+            ]
+        );
+    }
+}
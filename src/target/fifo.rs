@@ -0,0 +1,278 @@
+use crate::{LogLevel, Target, TargetId, error::Error};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    os::unix::fs::{FileTypeExt, OpenOptionsExt},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+// The values below come from the C headers of each platform, there is no libc
+// dependency to read them from. The module is only compiled where they were checked:
+// Linux and Android on x86, x86_64, arm, aarch64 and riscv64, macOS, iOS and FreeBSD.
+
+/// Value of `O_NONBLOCK`, to open the pipe without waiting for a reader.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const O_NONBLOCK: i32 = 0o4000;
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+const O_NONBLOCK: i32 = 0x4;
+
+/// Value of `ENXIO`, returned when opening a pipe no process has open for reading.
+const ENXIO: i32 = 6;
+
+/// Largest write to a pipe that is guaranteed to be atomic, `PIPE_BUF`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const PIPE_BUF: usize = 4096;
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+const PIPE_BUF: usize = 512;
+
+/// Target that writes to a named pipe (FIFO), read by another process.
+///
+/// The pipe is opened in non-blocking mode, so logging never waits for the reader:
+/// while no reader is attached, or while the reader is too slow to keep up,
+/// messages are dropped and counted (see [`FifoTarget::dropped`]).
+/// When a reader attaches again, the pipe is reopened on the next write.
+///
+/// Messages are written as with [`File`](crate::File), one per line, without colors.
+/// Each line is written at once, so the reader never gets half a line:
+/// lines longer than the atomic write size of a pipe (`PIPE_BUF`, 4096 bytes on Linux
+/// and 512 bytes on macOS, iOS and FreeBSD) are cut to fit.
+///
+/// Only available on macOS, iOS, FreeBSD, and Linux and Android
+/// on x86, x86_64, arm, aarch64 and riscv64.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, FifoTarget};
+///
+/// // Created beforehand with `mkfifo /tmp/app.log.pipe`
+/// let fifo = FifoTarget::new("/tmp/app.log.pipe").expect("Not a named pipe");
+///
+/// traccia::init_with_config(Config::default().with_target(fifo));
+/// ```
+#[derive(Clone)]
+pub struct FifoTarget {
+    path: PathBuf,
+    pipe: Arc<Mutex<Option<fs::File>>>,
+    dropped: Arc<AtomicU64>,
+    level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+}
+
+impl FifoTarget {
+    /// Creates a target writing to the named pipe at `path`.
+    ///
+    /// The pipe is not opened until the first write, so no reader is needed yet.
+    ///
+    /// # Returns
+    ///
+    /// A new `FifoTarget`, or an error if `path` doesn't exist or is not a named pipe
+    pub fn new<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        if !fs::metadata(path)?.file_type().is_fifo() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a named pipe", path.display()),
+            )
+            .into());
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            pipe: Arc::new(Mutex::new(None)),
+            dropped: Arc::new(AtomicU64::new(0)),
+            level: None,
+            only_levels: None,
+        })
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`](crate::File::only_level).
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+
+    /// Returns how many messages have been dropped,
+    /// because no reader was attached or the pipe was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Writes the bytes with a single write, opening the pipe if needed.
+    ///
+    /// The bytes must not be longer than `PIPE_BUF`, so that they are written
+    /// entirely or not at all.
+    ///
+    /// Returns `Ok(false)` if there is no reader or the pipe is full.
+    fn send(&self, bytes: &[u8]) -> io::Result<bool> {
+        debug_assert!(bytes.len() <= PIPE_BUF);
+
+        let mut pipe = self
+            .pipe
+            .lock()
+            .map_err(|_| io::Error::other("the pipe mutex is poisoned"))?;
+
+        if pipe.is_none() {
+            match OpenOptions::new()
+                .write(true)
+                .custom_flags(O_NONBLOCK)
+                .open(&self.path)
+            {
+                Ok(file) => *pipe = Some(file),
+                Err(e) if e.raw_os_error() == Some(ENXIO) => return Ok(false),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let Some(file) = pipe.as_mut() else {
+            return Ok(false);
+        };
+
+        match file.write(bytes) {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(false),
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                *pipe = None;
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Target for FifoTarget {
+    /// Writes the message to the pipe,
+    /// or drops it if no reader is attached or the pipe is full.
+    /// Lines longer than `PIPE_BUF` are cut to fit.
+    fn write(&self, _: LogLevel, formatted: &str) -> Result<(), Error> {
+        let mut end = formatted.len().min(PIPE_BUF - 1);
+
+        while !formatted.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let line = format!("{}\n", &formatted[..end]);
+
+        if !self.send(line.as_bytes())? {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+
+    /// Writes the bytes as they are to the pipe,
+    /// or drops them if no reader is attached or the pipe is full.
+    /// Bytes past `PIPE_BUF` are cut.
+    fn write_bytes(&self, _: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        if !self.send(&bytes[..bytes.len().min(PIPE_BUF)])? {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the path is still a named pipe.
+    fn validate(&self) -> Result<(), Error> {
+        Self::new(&self.path).map(drop)
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    /// Returns `false`, readers of the pipe get plain text.
+    fn wants_color(&self) -> bool {
+        false
+    }
+
+    fn id(&self) -> TargetId {
+        TargetId::File(self.path.clone())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::{io::Read, process::Command};
+
+    fn reader(path: &Path) -> fs::File {
+        OpenOptions::new()
+            .read(true)
+            .custom_flags(O_NONBLOCK)
+            .open(path)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reader_reconnects() {
+        let path = std::env::temp_dir().join(format!("traccia-fifo-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let created = Command::new("mkfifo").arg(&path).status();
+
+        if !created.is_ok_and(|status| status.success()) {
+            return;
+        }
+
+        let fifo = FifoTarget::new(&path).unwrap();
+
+        fifo.write(LogLevel::Info, "nobody listens").unwrap();
+        assert_eq!(fifo.dropped(), 1);
+
+        let mut first = reader(&path);
+        fifo.write(LogLevel::Info, "first").unwrap();
+
+        let mut read = String::new();
+        first.read_to_string(&mut read).ok();
+        assert_eq!(read, "first\n");
+
+        drop(first);
+        fifo.write(LogLevel::Info, "reader gone").unwrap();
+        assert_eq!(fifo.dropped(), 2);
+
+        let mut second = reader(&path);
+        fifo.write(LogLevel::Info, "second").unwrap();
+
+        let mut read = String::new();
+        second.read_to_string(&mut read).ok();
+        assert_eq!(read, "second\n");
+
+        fifo.write(LogLevel::Info, &"é".repeat(PIPE_BUF)).unwrap();
+
+        let mut read = String::new();
+        second.read_to_string(&mut read).ok();
+        assert_eq!(read, format!("{}\n", "é".repeat(PIPE_BUF / 2 - 1)));
+
+        assert!(FifoTarget::new(std::env::temp_dir()).is_err());
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,385 @@
+use crate::{LogLevel, Record, Target, TargetId, error::Error, util};
+use std::{
+    fs, io,
+    os::unix::net::UnixDatagram,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Path of the socket used by journald for the native protocol.
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Maximum size of an entry, kept below the default socket send buffer,
+/// past which the datagram would be rejected.
+const MAX_ENTRY_SIZE: usize = 128 * 1024;
+
+/// Fields written by the target or interpreted by journald, that span fields can't overwrite.
+const RESERVED_FIELDS: &[&str] = &[
+    "MESSAGE",
+    "MESSAGE_ID",
+    "PRIORITY",
+    "CODE_FILE",
+    "CODE_LINE",
+    "CODE_FUNC",
+    "CODE_MODULE",
+    "ERRNO",
+    "INVOCATION_ID",
+    "USER_INVOCATION_ID",
+    "SYSLOG_FACILITY",
+    "SYSLOG_IDENTIFIER",
+    "SYSLOG_PID",
+    "SYSLOG_TIMESTAMP",
+    "SYSLOG_RAW",
+    "DOCUMENTATION",
+    "TID",
+    "TRACE_ID",
+    "TRUNCATED",
+];
+
+/// Target that sends records to the systemd journal, through its native protocol.
+///
+/// Besides the message, each entry carries the priority, the source location,
+/// the module path, the trace id as `TRACE_ID` and the span fields, so they can be
+/// searched with `journalctl` (e.g. `journalctl USER_ID=42`).
+///
+/// Span field keys are uppercased, and any character other than letters,
+/// digits and underscores is replaced with an underscore. Keys matching a field
+/// set by the target or interpreted by journald, such as `MESSAGE` or `PRIORITY`,
+/// are prefixed with `FIELD_`.
+///
+/// Entries are sent as a single datagram, so they are limited to 128 KiB.
+/// Larger messages are truncated to fit, with their original length in the
+/// `TRUNCATED` field. Entries whose other fields alone exceed the limit are rejected
+/// with an error.
+///
+/// Only available on Linux, with the `journald` feature.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, JournaldTarget};
+///
+/// if let Ok(journald) = JournaldTarget::new() {
+///     let config = Config {
+///         targets: vec![Box::new(journald)],
+///         ..Config::default()
+///     };
+///
+///     traccia::init_with_config(config);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct JournaldTarget {
+    socket: Arc<UnixDatagram>,
+    path: PathBuf,
+    identifier: Option<String>,
+    level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+}
+
+impl JournaldTarget {
+    /// Creates a target writing to the journal socket.
+    ///
+    /// # Returns
+    ///
+    /// A new `JournaldTarget`, or an error if the journal socket doesn't exist,
+    /// which usually means the process is not running under systemd.
+    pub fn new() -> Result<Self, Error> {
+        Self::with_socket(JOURNAL_SOCKET)
+    }
+
+    /// Creates a target writing to a custom socket path.
+    pub fn with_socket<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("journal socket {} not found", path.display()),
+            )
+            .into());
+        }
+
+        Ok(Self {
+            socket: Arc::new(UnixDatagram::unbound()?),
+            path: path.to_path_buf(),
+            identifier: None,
+            level: None,
+            only_levels: None,
+        })
+    }
+
+    /// Returns `true` if the journal socket exists on this system.
+    pub fn is_available() -> bool {
+        Path::new(JOURNAL_SOCKET).exists()
+    }
+
+    /// Builder method to set the `SYSLOG_IDENTIFIER` field of the entries.
+    ///
+    /// By default journald uses the name of the executable.
+    pub fn identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.identifier = Some(identifier.into());
+        self
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`](crate::File::only_level).
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+
+    fn send(&self, record: &Record, message: &str) -> Result<(), Error> {
+        let payload = fit(record, message, self.identifier.as_deref())?;
+
+        self.socket.send_to(&payload, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Turns a key into a valid journal field name.
+///
+/// Field names are made of uppercase letters, digits and underscores,
+/// and can't start with an underscore or a digit. Reserved names are prefixed with `FIELD_`.
+fn field_name(key: &str) -> String {
+    let name = key
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' => c.to_ascii_uppercase(),
+            _ => '_',
+        })
+        .collect::<String>();
+
+    let name = name.trim_start_matches(|c: char| c == '_' || c.is_ascii_digit());
+
+    if name.is_empty() {
+        "FIELD".to_string()
+    } else if RESERVED_FIELDS.contains(&name) {
+        format!("FIELD_{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Appends a field to the payload, with the encoding required by the native protocol.
+///
+/// Values containing a newline are written as the field name, a newline,
+/// the value length as a little endian `u64`, and the raw value.
+fn push_field(payload: &mut Vec<u8>, name: &str, value: &str) {
+    payload.extend_from_slice(name.as_bytes());
+
+    if value.contains('\n') {
+        payload.push(b'\n');
+        payload.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        payload.push(b'=');
+    }
+
+    payload.extend_from_slice(value.as_bytes());
+    payload.push(b'\n');
+}
+
+/// Encodes a record as a journal entry of at most [`MAX_ENTRY_SIZE`] bytes,
+/// truncating the message if needed.
+fn fit(record: &Record, message: &str, identifier: Option<&str>) -> Result<Vec<u8>, Error> {
+    let payload = entry(record, message, identifier, None);
+
+    if payload.len() <= MAX_ENTRY_SIZE {
+        return Ok(payload);
+    }
+
+    // The length of the message is written in the entry, so the overhead is the same
+    let overhead = payload.len() - message.len() + "TRUNCATED=\n".len() + 20;
+
+    let Some(available) = MAX_ENTRY_SIZE.checked_sub(overhead) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "journal entry of {} bytes exceeds the limit of {} bytes",
+                payload.len(),
+                MAX_ENTRY_SIZE
+            ),
+        )
+        .into());
+    };
+
+    let end = (0..=available)
+        .rev()
+        .find(|&end| message.is_char_boundary(end))
+        .unwrap_or(0);
+
+    Ok(entry(
+        record,
+        &message[..end],
+        identifier,
+        Some(message.len()),
+    ))
+}
+
+/// Encodes a record as a journal entry.
+///
+/// `truncated` is the original length of the message, if it was truncated.
+fn entry(
+    record: &Record,
+    message: &str,
+    identifier: Option<&str>,
+    truncated: Option<usize>,
+) -> Vec<u8> {
+    let mut payload = Vec::new();
+
+    push_field(
+        &mut payload,
+        "PRIORITY",
+        &util::syslog_severity(record.level).to_string(),
+    );
+    push_field(&mut payload, "MESSAGE", message);
+
+    if let Some(length) = truncated {
+        push_field(&mut payload, "TRUNCATED", &length.to_string());
+    }
+
+    if let Some(identifier) = identifier {
+        push_field(&mut payload, "SYSLOG_IDENTIFIER", identifier);
+    }
+
+    if let Some(file) = record.file {
+        push_field(&mut payload, "CODE_FILE", file);
+    }
+
+    if let Some(line) = record.line {
+        push_field(&mut payload, "CODE_LINE", &line.to_string());
+    }
+
+    if let Some(module_path) = record.module_path {
+        push_field(&mut payload, "CODE_MODULE", module_path);
+    }
+
+    if let Some(trace_id) = &record.trace_id {
+        push_field(&mut payload, "TRACE_ID", trace_id);
+    }
+
+    for span in &record.context {
+        for (key, value) in &span.fields {
+            push_field(&mut payload, &field_name(key), value);
+        }
+    }
+
+    payload
+}
+
+impl Target for JournaldTarget {
+    /// Sends a plain message to the journal, with the priority of its level.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        let record = Record::builder(level, formatted).build();
+        self.send(&record, formatted)
+    }
+
+    /// Sends the record to the journal.
+    ///
+    /// The message field holds the raw record message, since the journal
+    /// already stores the priority and the other fields separately.
+    fn write_record(&self, record: &Record, _: &str) -> Result<(), Error> {
+        self.send(record, &record.message)
+    }
+
+    /// Checks that the journal socket still exists.
+    fn validate(&self) -> Result<(), Error> {
+        fs::metadata(&self.path)?;
+        Ok(())
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    /// Returns `false`, the journal stores plain text.
+    fn wants_color(&self) -> bool {
+        false
+    }
+
+    fn id(&self) -> TargetId {
+        TargetId::Custom("journald".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Span;
+
+    #[test]
+    fn test_field_name() {
+        assert_eq!(field_name("user_id"), "USER_ID");
+        assert_eq!(field_name("http.method"), "HTTP_METHOD");
+        assert_eq!(field_name("_9lives"), "LIVES");
+        assert_eq!(field_name("__"), "FIELD");
+        assert_eq!(field_name("message"), "FIELD_MESSAGE");
+        assert_eq!(field_name("priority"), "FIELD_PRIORITY");
+    }
+
+    #[test]
+    fn test_large_message_truncated() {
+        let record = Record::builder(LogLevel::Info, "é".repeat(MAX_ENTRY_SIZE)).build();
+        let payload = fit(&record, &record.message, None).unwrap();
+
+        assert!(payload.len() <= MAX_ENTRY_SIZE);
+        assert!(
+            String::from_utf8(payload)
+                .unwrap()
+                .contains(&format!("\nTRUNCATED={}\n", MAX_ENTRY_SIZE * 2))
+        );
+
+        let record = Record::builder(LogLevel::Info, "huge")
+            .context(vec![Span::new(
+                "request",
+                vec![("body".to_string(), "x".repeat(MAX_ENTRY_SIZE))],
+            )])
+            .build();
+
+        assert!(fit(&record, &record.message, None).is_err());
+    }
+
+    #[test]
+    fn test_entry_encoding() {
+        let record = Record::builder(LogLevel::Warn, "low disk")
+            .file("src/main.rs")
+            .line(7)
+            .context(vec![Span::new(
+                "request",
+                vec![("user_id".to_string(), "42".to_string())],
+            )])
+            .build();
+
+        assert_eq!(
+            entry(&record, &record.message, None, None),
+            b"PRIORITY=4\nMESSAGE=low disk\nCODE_FILE=src/main.rs\nCODE_LINE=7\nUSER_ID=42\n"
+        );
+
+        let record = Record::builder(LogLevel::Error, "a\nb").build();
+        let mut expected = b"PRIORITY=3\nMESSAGE\n".to_vec();
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(b"a\nb\n");
+
+        assert_eq!(entry(&record, &record.message, None, None), expected);
+    }
+}
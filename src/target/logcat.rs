@@ -0,0 +1,144 @@
+use crate::{LogLevel, Target, TargetId, error::Error};
+use std::{
+    ffi::{CString, c_char, c_int},
+    io,
+};
+
+#[link(name = "log")]
+unsafe extern "C" {
+    fn __android_log_write(priority: c_int, tag: *const c_char, text: *const c_char) -> c_int;
+}
+
+/// Target that writes to the Android log, shown by `logcat`.
+///
+/// Each message is written with the priority matching its level and the configured tag.
+/// ANSI codes are stripped, as `logcat` shows them as raw escape sequences.
+///
+/// Only available on Android, with the `android` feature.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, LogcatTarget};
+///
+/// let config = Config {
+///     targets: vec![Box::new(LogcatTarget::new("my-app"))],
+///     ..Config::default()
+/// };
+///
+/// traccia::init_with_config(config);
+/// ```
+#[derive(Clone)]
+pub struct LogcatTarget {
+    tag: CString,
+    level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+}
+
+impl LogcatTarget {
+    /// Creates a target writing to the Android log with the given tag.
+    ///
+    /// Nul bytes in the tag are removed.
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            tag: c_string(tag.into()),
+            level: None,
+            only_levels: None,
+        }
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`](crate::File::only_level).
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+}
+
+/// Maps a level to an Android log priority.
+fn priority(level: LogLevel) -> c_int {
+    match level.severity() {
+        0 => 2, // ANDROID_LOG_VERBOSE
+        1 => 3, // ANDROID_LOG_DEBUG
+        2 => 4, // ANDROID_LOG_INFO
+        3 => 5, // ANDROID_LOG_WARN
+        4 => 6, // ANDROID_LOG_ERROR
+        _ => 7, // ANDROID_LOG_FATAL
+    }
+}
+
+/// Converts the string to a C string, removing any nul byte.
+fn c_string(s: String) -> CString {
+    CString::new(s).unwrap_or_else(|e| {
+        let mut bytes = e.into_vec();
+        bytes.retain(|&b| b != 0);
+
+        // No nul bytes left, so this can't fail.
+        CString::new(bytes).unwrap_or_default()
+    })
+}
+
+impl Target for LogcatTarget {
+    /// Writes the log message to the Android log.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        let text = c_string(formatted.to_string());
+
+        // SAFETY: both pointers come from valid, nul terminated C strings
+        // that outlive the call.
+        let result =
+            unsafe { __android_log_write(priority(level), self.tag.as_ptr(), text.as_ptr()) };
+
+        if result < 0 {
+            return Err(io::Error::from_raw_os_error(-result).into());
+        }
+
+        Ok(())
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    /// Returns `false`, logcat doesn't render ANSI codes.
+    fn wants_color(&self) -> bool {
+        false
+    }
+
+    fn id(&self) -> TargetId {
+        TargetId::Custom("logcat".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority() {
+        assert_eq!(priority(LogLevel::Trace), 2);
+        assert_eq!(priority(LogLevel::Warn), 5);
+        assert_eq!(priority(LogLevel::Fatal), 7);
+    }
+
+    #[test]
+    fn test_c_string_removes_nul_bytes() {
+        assert_eq!(c_string("a\0b".to_string()).as_bytes(), b"ab");
+    }
+}
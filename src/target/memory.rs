@@ -0,0 +1,254 @@
+use crate::{LogLevel, Target, TargetId, error::Error};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, mpsc},
+};
+
+/// Number of messages a receiver returned by [`MemoryTarget::subscribe`] can hold.
+const DEFAULT_SUBSCRIBER_CAPACITY: usize = 1024;
+
+/// Target that keeps the formatted messages in memory.
+///
+/// Useful for tests, and as a backend for in-app log viewers:
+/// the buffer can be bounded with [`MemoryTarget::with_capacity`], evicting the oldest
+/// messages, and [`MemoryTarget::subscribe`] delivers new messages as they are logged.
+///
+/// Clones share the same buffer, so keeping a clone of the target
+/// before adding it to the config allows reading the messages later.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, MemoryTarget};
+///
+/// let memory = MemoryTarget::with_capacity(1000);
+/// let receiver = memory.subscribe();
+///
+/// traccia::init_with_config(Config {
+///     targets: vec![Box::new(memory.clone())],
+///     ..Config::default()
+/// });
+///
+/// std::thread::spawn(move || {
+///     for (level, message) in receiver {
+///         // Append to the log pane
+///     }
+/// });
+/// ```
+#[derive(Clone, Default)]
+pub struct MemoryTarget {
+    state: Arc<Mutex<MemoryState>>,
+    level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    capacity: Option<usize>,
+    entries: VecDeque<(LogLevel, String)>,
+    subscribers: Vec<mpsc::SyncSender<(LogLevel, String)>>,
+}
+
+impl MemoryTarget {
+    /// Creates an unbounded memory target.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a memory target keeping at most `capacity` messages.
+    ///
+    /// The buffer grows as messages are written, up to `capacity`.
+    /// When it is full, the oldest message is evicted.
+    /// Subscribers still receive every message.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let target = Self::default();
+
+        if let Ok(mut state) = target.state.lock() {
+            state.capacity = Some(capacity);
+        }
+
+        target
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`](crate::File::only_level).
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+
+    /// Returns a copy of the messages currently in the buffer, oldest first.
+    pub fn entries(&self) -> Vec<(LogLevel, String)> {
+        self.state
+            .lock()
+            .map(|state| state.entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Removes and returns the messages currently in the buffer, oldest first.
+    pub fn drain(&self) -> Vec<(LogLevel, String)> {
+        self.state
+            .lock()
+            .map(|mut state| state.entries.drain(..).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of messages in the buffer.
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .map(|state| state.entries.len())
+            .unwrap_or(0)
+    }
+
+    /// Returns `true` if the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a receiver for the messages written from now on,
+    /// holding up to 1024 messages.
+    ///
+    /// See [`MemoryTarget::subscribe_with_capacity`].
+    pub fn subscribe(&self) -> mpsc::Receiver<(LogLevel, String)> {
+        self.subscribe_with_capacity(DEFAULT_SUBSCRIBER_CAPACITY)
+    }
+
+    /// Returns a receiver for the messages written from now on,
+    /// holding up to `capacity` messages.
+    ///
+    /// While the receiver is full, new messages are not sent to it,
+    /// so a subscriber that stops reading doesn't grow the memory usage.
+    /// Subscribers are removed when their receiver is dropped.
+    pub fn subscribe_with_capacity(&self, capacity: usize) -> mpsc::Receiver<(LogLevel, String)> {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+
+        if let Ok(mut state) = self.state.lock() {
+            state.subscribers.push(sender);
+        }
+
+        receiver
+    }
+}
+
+impl Target for MemoryTarget {
+    /// Stores the message, evicting the oldest one if the buffer is full,
+    /// and sends it to the subscribers.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        let mut state = self.state.lock().map_err(|_| Error::Poisoned)?;
+
+        state.subscribers.retain(|subscriber| {
+            !matches!(
+                subscriber.try_send((level, formatted.to_string())),
+                Err(mpsc::TrySendError::Disconnected(_))
+            )
+        });
+
+        if state.capacity == Some(0) {
+            return Ok(());
+        }
+
+        if state.capacity == Some(state.entries.len()) {
+            state.entries.pop_front();
+        }
+
+        state.entries.push_back((level, formatted.to_string()));
+
+        Ok(())
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    fn id(&self) -> TargetId {
+        TargetId::Custom("memory".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_evicts_oldest() {
+        let memory = MemoryTarget::with_capacity(2);
+
+        for message in ["a", "b", "c"] {
+            memory.write(LogLevel::Info, message).unwrap();
+        }
+
+        assert_eq!(
+            memory.drain(),
+            [
+                (LogLevel::Info, "b".to_string()),
+                (LogLevel::Info, "c".to_string())
+            ]
+        );
+        assert!(memory.is_empty());
+    }
+
+    #[test]
+    fn test_only_levels() {
+        let memory = MemoryTarget::new().only_levels(&[LogLevel::Warn, LogLevel::Fatal]);
+
+        assert!(memory.accepts(LogLevel::Warn));
+        assert!(!memory.accepts(LogLevel::Error));
+        assert!(memory.clone().accepts(LogLevel::Fatal));
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let memory = MemoryTarget::new();
+
+        memory.write(LogLevel::Info, "before").unwrap();
+
+        let receiver = memory.subscribe();
+        let dropped = memory.subscribe();
+
+        drop(dropped);
+
+        memory.clone().write(LogLevel::Warn, "after").unwrap();
+
+        assert_eq!(
+            receiver.try_recv(),
+            Ok((LogLevel::Warn, "after".to_string()))
+        );
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(memory.state.lock().unwrap().subscribers.len(), 1);
+        assert_eq!(memory.len(), 2);
+    }
+
+    #[test]
+    fn test_subscriber_capacity() {
+        let memory = MemoryTarget::new();
+        let receiver = memory.subscribe_with_capacity(1);
+
+        memory.write(LogLevel::Info, "kept").unwrap();
+        memory.write(LogLevel::Info, "skipped").unwrap();
+
+        assert_eq!(
+            receiver.try_recv(),
+            Ok((LogLevel::Info, "kept".to_string()))
+        );
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(memory.state.lock().unwrap().subscribers.len(), 1);
+    }
+}
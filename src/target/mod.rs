@@ -0,0 +1,1388 @@
+/// Target module defining output destinations for log messages.
+use crate::{ColorMode, LogLevel, Record, error::Error, util};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{self, IsTerminal, Write},
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+mod channel;
+#[cfg(feature = "clipboard")]
+mod clipboard;
+mod decorate;
+#[cfg(any(
+    all(
+        any(target_os = "linux", target_os = "android"),
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv64"
+        )
+    ),
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd"
+))]
+mod fifo;
+#[cfg(all(feature = "journald", target_os = "linux"))]
+mod journald;
+#[cfg(all(feature = "android", target_os = "android"))]
+mod logcat;
+mod memory;
+mod named;
+mod non_blocking;
+#[cfg(feature = "otlp")]
+mod otlp;
+mod rolling;
+mod sampler;
+mod sharded;
+mod split;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+mod wasm;
+
+pub use channel::{ChannelItem, ChannelTarget};
+#[cfg(feature = "clipboard")]
+pub use clipboard::ClipboardTarget;
+pub use decorate::Decorate;
+#[cfg(any(
+    all(
+        any(target_os = "linux", target_os = "android"),
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv64"
+        )
+    ),
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd"
+))]
+pub use fifo::FifoTarget;
+#[cfg(all(feature = "journald", target_os = "linux"))]
+pub use journald::JournaldTarget;
+#[cfg(all(feature = "android", target_os = "android"))]
+pub use logcat::LogcatTarget;
+pub use memory::MemoryTarget;
+pub use named::Named;
+pub use non_blocking::{NonBlocking, QueueFull, WorkerGuard};
+#[cfg(feature = "otlp")]
+pub use otlp::OtlpTarget;
+pub use rolling::{RollingFile, RotationPeriod};
+pub use sampler::Sampler;
+pub use sharded::{ShardedFile, Sharding};
+pub use split::LevelSplit;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub use wasm::WasmConsoleTarget;
+
+/// Workaround to be able to clone boxed trait objects.
+pub trait TargetClone {
+    fn clone_box(&self) -> Box<dyn Target>;
+}
+
+impl<T> TargetClone for T
+where
+    T: 'static + Target + Clone,
+{
+    fn clone_box(&self) -> Box<dyn Target> {
+        Box::new(self.clone())
+    }
+}
+
+/// Represents the id of the target where the log message is written.
+/// This can be a console output, file path, or a custom string identifier.
+/// Useful for identifying the target in hooks / filters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TargetId {
+    /// Console output target
+    Console(Output),
+    /// File output target
+    File(PathBuf),
+    /// Custom target identifier
+    /// This can be used for custom targets that don't fit into the
+    /// predefined categories.
+    Custom(String),
+}
+
+/// Renders the id as `console:stdout`, `file:<path>` or `custom:<name>`.
+impl std::fmt::Display for TargetId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TargetId::Console(output) => write!(f, "console:{}", output),
+            TargetId::File(path) => write!(f, "file:{}", path.display()),
+            TargetId::Custom(name) => write!(f, "custom:{}", name),
+        }
+    }
+}
+
+/// Defines an output destination for log messages.
+///
+/// This trait allows the logger to write formatted messages to different
+/// destinations such as console, files, or custom targets.
+///
+/// Implementors must be thread-safe (Send + Sync) and cloneable.
+pub trait Target: Send + Sync + TargetClone {
+    /// Writes a formatted log message to the target.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message
+    /// * `formatted` - The formatted log message to write
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if successful, or an error if the write operation failed
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error>;
+
+    /// Writes raw bytes to the target.
+    ///
+    /// Useful for payloads that are not valid UTF-8, such as binary or
+    /// length-prefixed frames. The default implementation converts the bytes
+    /// to a string, replacing invalid sequences, and calls `write`.
+    /// Targets that can write bytes directly should override it
+    /// and write the bytes as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the payload
+    /// * `bytes` - The bytes to write
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if successful, or an error if the write operation failed
+    fn write_bytes(&self, level: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        self.write(level, &String::from_utf8_lossy(bytes))
+    }
+
+    /// Writes a log record to the target.
+    ///
+    /// The loggers call this method, so that targets needing more than the
+    /// formatted message (e.g. the source location or the span fields) can access the record.
+    /// The default implementation calls `write`.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The record being logged
+    /// * `formatted` - The record formatted by the configured formatter
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if successful, or an error if the write operation failed
+    fn write_record(&self, record: &Record, formatted: &str) -> Result<(), Error> {
+        self.write(record.level, formatted)
+    }
+
+    /// Writes several log records at once.
+    ///
+    /// The async logger uses this to hand over all the records queued
+    /// for the target, already filtered by level. Targets can override it
+    /// to reduce locking and syscalls, the default implementation
+    /// calls `write_record` for each record.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - Each record with its formatted message, in order
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if every write succeeded, or the first error encountered.
+    /// The records after a failed one are still written.
+    fn write_batch(&self, batch: &[(&Record, &str)]) -> Result<(), Error> {
+        let mut result = Ok(());
+
+        for (record, formatted) in batch {
+            if let Err(e) = self.write_record(record, formatted)
+                && result.is_ok()
+            {
+                result = Err(e);
+            }
+        }
+
+        result
+    }
+
+    /// Flushes the data buffered by the target, if any.
+    ///
+    /// The async logger calls it when [`flush`](crate::flush()) is called,
+    /// when shutting down, and periodically while idle
+    /// (see [`Config::flush_interval`](crate::Config::flush_interval)).
+    /// The default implementation does nothing.
+    fn flush(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Checks that the target is able to write, without writing anything.
+    ///
+    /// Called by [`Config::validate`](crate::Config::validate), so misconfigured targets
+    /// can be reported at startup instead of at the first write.
+    /// The default implementation does nothing.
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Returns a custom filter level for the target.
+    /// If the target has a filter level set, log messages with a lower
+    /// level will be ignored.
+    fn filter_level(&self) -> Option<LogLevel> {
+        None
+    }
+
+    /// Returns the maximum level accepted by the target.
+    /// If set, log messages with a higher level will be ignored.
+    fn max_level(&self) -> Option<LogLevel> {
+        None
+    }
+
+    /// Returns the only levels accepted by the target.
+    /// If set, log messages with any other level will be ignored.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        None
+    }
+
+    /// Returns `true` if the target wants the ANSI color codes in the formatted messages.
+    ///
+    /// When it returns `false`, the loggers strip the colors before writing to the target,
+    /// so targets writing plain text don't need to do it themselves.
+    /// The default implementation returns `true`.
+    fn wants_color(&self) -> bool {
+        true
+    }
+
+    /// Returns `true` if the target accepts messages with the given level,
+    /// that is, if the level is within `filter_level` and `max_level`
+    /// and is one of `only_levels`.
+    fn accepts(&self, level: LogLevel) -> bool {
+        self.filter_level().is_none_or(|min| min.includes(level))
+            && self.max_level().is_none_or(|max| level <= max)
+            && self.only_levels().is_none_or(|only| only.contains(&level))
+    }
+
+    /// Returns the target ID for the target.
+    /// This is used to identify the target in the logger.
+    ///
+    /// The default implementation is based on the address of the target,
+    /// which changes across runs. Wrap the target in [`Named`]
+    /// to give it a stable id.
+    fn id(&self) -> TargetId {
+        TargetId::Custom(format!("{:p}", self))
+    }
+
+    /// Returns `true` for the console of the default configuration,
+    /// which is replaced by the first target added with [`Config::with_target`](crate::Config::with_target).
+    #[doc(hidden)]
+    fn is_default(&self) -> bool {
+        false
+    }
+}
+
+impl Clone for Box<dyn Target> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Output destination for console log messages.
+///
+/// The default output is stdout.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Output {
+    /// Standard output (stdout)
+    #[default]
+    Stdout,
+    /// Standard error (stderr)
+    Stderr,
+}
+
+/// Renders the output as `stdout` or `stderr`.
+impl std::fmt::Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Output::Stdout => write!(f, "stdout"),
+            Output::Stderr => write!(f, "stderr"),
+        }
+    }
+}
+
+impl Default for &Output {
+    fn default() -> Self {
+        &Output::Stdout
+    }
+}
+
+/// A writer shared between threads, used to redirect the console output.
+pub type SharedWriter = Arc<Mutex<dyn Write + Send>>;
+
+/// Standard console output target.
+///
+/// This target writes log messages to the standard output (stdout) or standard error (stderr)
+/// using the Rust `println!` | `eprintln!` macro.
+/// The outputs can be redirected to custom writers with `writers`, e.g. to capture them in tests.
+///
+/// Use [`Console::stdout`] or [`Console::stderr`] to write every message to a single stream.
+#[derive(Default, Clone)]
+pub struct Console {
+    level: Option<LogLevel>,
+    max_level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+    output: Option<Output>,
+    stderr_from: Option<LogLevel>,
+    filtered_outputs: Option<HashMap<LogLevel, Output>>,
+    name: Option<String>,
+    writers: Option<(SharedWriter, SharedWriter)>,
+    color_mode: ColorMode,
+    normalize_line_endings: bool,
+    default: bool,
+}
+
+impl std::fmt::Debug for Console {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Console")
+            .field("level", &self.level)
+            .field("max_level", &self.max_level)
+            .field("only_levels", &self.only_levels)
+            .field("output", &self.output)
+            .field("stderr_from", &self.stderr_from)
+            .field("filtered_outputs", &self.filtered_outputs)
+            .field("name", &self.name)
+            .field("custom_writers", &self.writers.is_some())
+            .field("color_mode", &self.color_mode)
+            .field("normalize_line_endings", &self.normalize_line_endings)
+            .finish()
+    }
+}
+
+impl Console {
+    /// Creates a new console target
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the console target of the default configuration,
+    /// replaced by the first target added with [`Config::with_target`](crate::Config::with_target).
+    pub(crate) fn default_target() -> Self {
+        Self {
+            default: true,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a console target writing to stdout.
+    ///
+    /// Shorthand for `Console::new().output(Output::Stdout)`.
+    pub fn stdout() -> Self {
+        Self::new().output(Output::Stdout)
+    }
+
+    /// Creates a console target writing to stderr.
+    ///
+    /// Shorthand for `Console::new().output(Output::Stderr)`.
+    pub fn stderr() -> Self {
+        Self::new().output(Output::Stderr)
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    ///
+    /// (e.g. `min_level(LogLevel::Warn)` accepts warnings, errors and fatal errors)
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`].
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+
+    /// Builder method to set the custom filter level for this target.
+    #[deprecated(note = "use `min_level`, which has the same behavior")]
+    pub fn filtered(self, level: LogLevel) -> Self {
+        self.min_level(level)
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// between `min` and `max` (both inclusive).
+    ///
+    /// (e.g. `level_range(LogLevel::Debug, LogLevel::Warn)` excludes errors,
+    /// which can then be written to a different target)
+    pub fn level_range(mut self, min: LogLevel, max: LogLevel) -> Self {
+        self.level = Some(min);
+        self.max_level = Some(max);
+        self
+    }
+
+    /// Builder method to set the custom output for the console.
+    /// This will write to the output for all the logs that target this console.
+    ///
+    /// If you want to set the output for a specific log level, use `filtered_outputs`.
+    ///
+    /// (e.g. You want to write all `LogLevel::Error` logs to stderr and all other logs to stdout)
+    pub fn output(mut self, output: Output) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Builder method to set the custom output for a specific log level.
+    /// This behaves like the `output` method, but only applies to the specified log level.
+    ///
+    /// If you want to set the output for all logs, use `output`.
+    /// Note: This will override the output set by `output`.
+    ///
+    /// (e.g. If you set `output` to `Stderr`, calling `filtered_outputs(LogLevel::Info, Output::Stdout)` will
+    /// log all `LogLevel::Info` logs to stdout and all other logs to stderr)
+    pub fn filtered_output(mut self, level: LogLevel, output: Output) -> Self {
+        self.filtered_outputs
+            .get_or_insert_with(HashMap::new)
+            .insert(level, output);
+
+        self
+    }
+
+    /// Builder method to write the logs at or above `level` to stderr,
+    /// and the logs below it to stdout.
+    ///
+    /// This takes precedence over `output`, while the outputs set with
+    /// `filtered_output` still take precedence over this.
+    ///
+    /// (e.g. `stderr_from(LogLevel::Warn)` writes warnings, errors and fatal errors to stderr)
+    pub fn stderr_from(mut self, level: LogLevel) -> Self {
+        self.stderr_from = Some(level);
+        self
+    }
+
+    /// Builder method to give the console a stable name.
+    ///
+    /// The id of the target becomes `TargetId::Custom(name)`,
+    /// useful to tell consoles apart in hooks.
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Builder method to write to custom writers instead of stdout and stderr.
+    ///
+    /// The output routing still applies: messages routed to stdout are written
+    /// to `stdout`, and messages routed to stderr to `stderr`.
+    /// The same writer can be passed twice to capture both outputs together.
+    ///
+    /// (e.g. In tests, pass an `Arc<Mutex<Vec<u8>>>` and read it back after logging)
+    pub fn writers(mut self, stdout: SharedWriter, stderr: SharedWriter) -> Self {
+        self.writers = Some((stdout, stderr));
+        self
+    }
+
+    /// Builder method to set whether and how colors are written.
+    ///
+    /// The default, `ColorMode::Auto`, writes colors only when the output is a terminal
+    /// and the environment allows it. Custom writers are never considered terminals.
+    /// Raw payloads written with `write_bytes` are never changed.
+    ///
+    /// (e.g. `color_mode(ColorMode::Never)` to always strip colors,
+    /// or `color_mode(ColorMode::Ansi256)` for terminals without RGB support)
+    pub fn color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+
+    /// Builder method to turn `\r\n` and `\r` line breaks into `\n`.
+    ///
+    /// Regardless of this setting, a single trailing line break is removed
+    /// from the messages, so that they don't end with a blank line.
+    pub fn normalize_line_endings(mut self) -> Self {
+        self.normalize_line_endings = true;
+        self
+    }
+
+    /// Returns `true` if the output is a terminal.
+    fn is_terminal(&self, output: Output) -> bool {
+        self.writers.is_none()
+            && match output {
+                Output::Stdout => io::stdout().is_terminal(),
+                Output::Stderr => io::stderr().is_terminal(),
+            }
+    }
+
+    /// Writes the bytes to the custom writer of the output, if any.
+    ///
+    /// Returns `None` when no custom writers are set.
+    fn write_custom(&self, output: Output, bytes: &[u8]) -> Option<Result<(), Error>> {
+        let (stdout, stderr) = self.writers.as_ref()?;
+
+        let writer = match output {
+            Output::Stdout => stdout,
+            Output::Stderr => stderr,
+        };
+
+        let result = match writer.lock() {
+            Ok(mut writer) => writer.write_all(bytes).map_err(Error::from),
+            Err(_) => Err(Error::Poisoned),
+        };
+
+        Some(result)
+    }
+
+    /// Returns the output used for the given level.
+    fn output_for(&self, level: LogLevel) -> Output {
+        if let Some(output) = self
+            .filtered_outputs
+            .as_ref()
+            .and_then(|map| map.get(&level))
+        {
+            return *output;
+        }
+
+        match self.stderr_from {
+            Some(threshold) if level >= threshold => Output::Stderr,
+            Some(_) => Output::Stdout,
+            None => self.output.unwrap_or_default(),
+        }
+    }
+}
+
+impl Target for Console {
+    /// Writes the formatted log message to the console.
+    ///
+    /// A trailing line break in the message is removed, as one is always appended.
+    ///
+    /// # Arguments
+    ///
+    /// * `formatted` - The formatted log message to write
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())`, or an error if writing to a custom writer failed
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        let output = self.output_for(level);
+        let formatted = util::trim_newline(formatted, self.normalize_line_endings);
+        let formatted = self.color_mode.apply(&formatted, self.is_terminal(output));
+
+        // Only build the line for the custom writers, `println!` adds the line break itself
+        if self.writers.is_some()
+            && let Some(result) = self.write_custom(output, format!("{}\n", formatted).as_bytes())
+        {
+            return result;
+        }
+
+        match output {
+            Output::Stdout => println!("{}", formatted),
+            Output::Stderr => eprintln!("{}", formatted),
+        }
+
+        Ok(())
+    }
+
+    /// Writes the bytes as they are to stdout or stderr,
+    /// without appending a line terminator.
+    fn write_bytes(&self, level: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        let output = self.output_for(level);
+
+        if let Some(result) = self.write_custom(output, bytes) {
+            return result;
+        }
+
+        match output {
+            Output::Stdout => io::stdout().lock().write_all(bytes)?,
+            Output::Stderr => io::stderr().lock().write_all(bytes)?,
+        }
+
+        Ok(())
+    }
+
+    /// Flushes stdout and stderr, or the custom writers if set.
+    fn flush(&self) -> Result<(), Error> {
+        if let Some((stdout, stderr)) = &self.writers {
+            for writer in [stdout, stderr] {
+                writer.lock().map_err(|_| Error::Poisoned)?.flush()?;
+            }
+
+            return Ok(());
+        }
+
+        io::stdout().flush()?;
+        io::stderr().flush()?;
+
+        Ok(())
+    }
+
+    /// Returns the custom filter level for the console target.
+    /// If the filter level is set, log messages with a lower level
+    /// will be ignored.
+    ///
+    /// Useful for filtering log messages written to the console.
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the maximum level set with `level_range`, if any.
+    fn max_level(&self) -> Option<LogLevel> {
+        self.max_level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    /// Returns the target ID for the console target.
+    /// This is used to identify the target in the logger.
+    ///
+    /// If the console is named, the id is `TargetId::Custom` with the name.
+    fn id(&self) -> TargetId {
+        match &self.name {
+            Some(name) => TargetId::Custom(name.clone()),
+            None => TargetId::Console(self.output.unwrap_or_default()),
+        }
+    }
+
+    fn is_default(&self) -> bool {
+        self.default
+    }
+}
+
+/// File open mode for writing log messages.
+/// - `Append`: Open the file in append mode, preserving existing content
+/// - `Truncate`: Open the file in write mode, truncating existing content
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FileMode {
+    #[default]
+    Append,
+    Truncate,
+}
+
+/// How raw control characters in log messages are handled by a target.
+///
+/// Messages can contain untrusted input, and control characters such as
+/// `\n` or `\r` could be used to forge fake log lines.
+/// Tabs are always kept.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ControlChars {
+    /// Control characters are written as they are (default).
+    #[default]
+    Keep,
+    /// Control characters are escaped, e.g. `\n` becomes the two characters `\` and `n`.
+    Escape,
+    /// Control characters are removed.
+    Strip,
+}
+
+/// File output target.
+///
+/// This target writes log messages to a file on disk.
+/// The loggers strip the ANSI color codes from the messages written to files,
+/// see [`File::keep_colors`] to keep them.
+#[derive(Clone)]
+pub struct File {
+    path: Option<PathBuf>,
+    inner: Arc<Mutex<fs::File>>,
+    level: Option<LogLevel>,
+    max_level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+    control_chars: ControlChars,
+    keep_colors: bool,
+    normalize_line_endings: bool,
+    name: Option<String>,
+}
+
+impl Deref for File {
+    type Target = Mutex<fs::File>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl File {
+    /// Creates a new file target.
+    ///
+    /// This function will create the parent directories if they don't exist
+    /// and open the file in append mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the log file'
+    /// * `mode` - File open mode (default: `Append`)
+    ///
+    /// # Returns
+    ///
+    /// A new `File` target instance or an error if the file couldn't be opened
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use traccia::{Config, File, init_with_config, FileMode};
+    ///
+    /// let file_target = File::new("logs/app.log", FileMode::Append).expect("Failed to open log file");
+    /// let config = Config {
+    ///     targets: vec![Box::new(file_target)],
+    ///     ..Config::default()
+    /// };
+    /// init_with_config(config);
+    /// ```
+    pub fn new<P>(path: P, mode: FileMode) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut options = OpenOptions::new();
+
+        options.create(true);
+
+        match mode {
+            FileMode::Append => {
+                options.append(true);
+            }
+
+            FileMode::Truncate => {
+                options.write(true).truncate(true);
+            }
+        }
+
+        let file = options.open(path)?;
+        let mut target = Self::from_file(file, None);
+
+        target.path = Some(path.to_path_buf());
+
+        Ok(target)
+    }
+
+    /// Creates a file target from an already open file.
+    ///
+    /// The file is used as it is: no directory is created and no open option is applied,
+    /// so it must be opened for writing. Useful for files opened with specific flags,
+    /// temporary files or inherited file descriptors.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The open file to write to
+    /// * `id_label` - Name used as the target id, as with `named`
+    ///
+    /// # Returns
+    ///
+    /// A new `File` target, with the id `TargetId::Custom(label)`,
+    /// or `TargetId::Custom("file")` if no label is given
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use std::fs::OpenOptions;
+    /// use traccia::File;
+    ///
+    /// let file = OpenOptions::new()
+    ///     .create_new(true)
+    ///     .write(true)
+    ///     .open("app.log")
+    ///     .expect("Failed to open log file");
+    ///
+    /// let target = File::from_file(file, Some("app".to_string()));
+    /// ```
+    pub fn from_file(file: fs::File, id_label: Option<String>) -> Self {
+        File {
+            path: None,
+            inner: Arc::new(Mutex::new(file)),
+            level: None,
+            max_level: None,
+            only_levels: None,
+            control_chars: ControlChars::Keep,
+            keep_colors: false,
+            normalize_line_endings: false,
+            name: id_label,
+        }
+    }
+
+    /// Creates a new file target with a custom filter level.
+    ///
+    /// This is the same as calling `new` followed by `filtered`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the log file
+    /// * `mode` - File open mode
+    /// * `level` - Minimum level of the messages written to the file
+    ///
+    /// # Returns
+    ///
+    /// A new `File` target instance or an error if the file couldn't be opened
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{File, FileMode, LogLevel};
+    ///
+    /// let fatal_only = File::new_filtered("logs/fatal.log", FileMode::Append, LogLevel::Fatal)
+    ///     .expect("Failed to open log file");
+    /// ```
+    pub fn new_filtered<P>(path: P, mode: FileMode, level: LogLevel) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self::new(path, mode)?.min_level(level))
+    }
+
+    /// Only accepts log messages with a level at or above `level`.
+    ///
+    /// Useful for filtering log messages written to files.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Only accepts log messages with exactly the given level.
+    ///
+    /// Useful to keep a single level in its own file, e.g. `only_level(LogLevel::Warn)`
+    /// for a file with the warnings but not the errors.
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Only accepts log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+
+    /// Sets a custom filter level for the file target.
+    #[deprecated(note = "use `min_level`, which has the same behavior")]
+    pub fn filtered(self, level: LogLevel) -> Self {
+        self.min_level(level)
+    }
+
+    /// Only accepts log messages with a level between `min` and `max` (both inclusive).
+    ///
+    /// Useful for splitting logs across files, e.g. `Debug` to `Warn` in `debug.log`
+    /// and `Error` and above in `error.log`.
+    pub fn level_range(mut self, min: LogLevel, max: LogLevel) -> Self {
+        self.level = Some(min);
+        self.max_level = Some(max);
+        self
+    }
+
+    /// Sets how control characters in the messages are handled (default: `Keep`).
+    ///
+    /// With `Escape` or `Strip`, each record is guaranteed to take a single line,
+    /// which prevents log injection when logging untrusted input.
+    /// Note that multi-line messages, such as backtraces, end up on a single line too.
+    pub fn control_chars(mut self, control_chars: ControlChars) -> Self {
+        self.control_chars = control_chars;
+        self
+    }
+
+    /// Keeps the ANSI color codes in the messages written to the file.
+    ///
+    /// By default colors are stripped. Keeping them is useful for files
+    /// meant to be viewed with an ANSI-aware pager, such as `less -R`.
+    ///
+    /// The escape character is a control character, so colors are removed or escaped
+    /// anyway when `control_chars` is set to `Strip` or `Escape`.
+    pub fn keep_colors(mut self) -> Self {
+        self.keep_colors = true;
+        self
+    }
+
+    /// Turns `\r\n` and `\r` line breaks into `\n`, as with [`Console::normalize_line_endings`].
+    ///
+    /// Regardless of this setting, a single trailing line break is removed
+    /// from the messages, so that they don't end with a blank line.
+    pub fn normalize_line_endings(mut self) -> Self {
+        self.normalize_line_endings = true;
+        self
+    }
+
+    /// Gives the file target a stable name.
+    ///
+    /// The id of the target becomes `TargetId::Custom(name)` instead of the file path.
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Writes a header line to the file, followed by a line terminator.
+    ///
+    /// Useful to mark where each run of the application starts in a log file
+    /// opened in append mode. The header is written as it is, without stripping
+    /// the colors or handling the control characters, so it can span multiple lines.
+    ///
+    /// # Returns
+    ///
+    /// The file target, or an error if the header couldn't be written
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use traccia::{File, FileMode};
+    ///
+    /// let file = File::new("logs/app.log", FileMode::Append)?.with_header(format!(
+    ///     "--- {} {} (pid {}) ---",
+    ///     env!("CARGO_PKG_NAME"),
+    ///     env!("CARGO_PKG_VERSION"),
+    ///     std::process::id()
+    /// ))?;
+    /// ```
+    pub fn with_header(self, header: impl AsRef<str>) -> Result<Self, Error> {
+        let mut file = self.lock().map_err(|_| Error::Poisoned)?;
+        writeln!(file, "{}", header.as_ref())?;
+        drop(file);
+
+        Ok(self)
+    }
+
+    /// Prepares a message to be written to the file.
+    ///
+    /// Borrows the message when it has nothing to normalize or escape,
+    /// which is the common case.
+    fn clean<'a>(&self, formatted: &'a str) -> Cow<'a, str> {
+        let formatted = util::trim_newline(formatted, self.normalize_line_endings);
+
+        util::map_cow(formatted, |s| {
+            util::sanitize_control_chars(s, self.control_chars)
+        })
+    }
+}
+
+impl Target for File {
+    /// Writes the formatted log message to the file.
+    ///
+    /// The message is written with its colors: the loggers strip them beforehand,
+    /// unless `keep_colors` is set (see [`Target::wants_color`]).
+    /// Control characters are handled as set with `control_chars`.
+    ///
+    /// # Arguments
+    ///
+    /// * `formatted` - The formatted log message to write
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if successful, or an error if the write operation failed
+    fn write(&self, _: LogLevel, formatted: &str) -> Result<(), Error> {
+        let mut file = self.lock().map_err(|_| Error::Poisoned)?;
+        writeln!(file, "{}", self.clean(formatted))?;
+        Ok(())
+    }
+
+    /// Writes the bytes as they are to the file.
+    ///
+    /// Colors are not stripped, control characters are kept
+    /// and no line terminator is appended.
+    fn write_bytes(&self, _: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        let mut file = self.lock().map_err(|_| Error::Poisoned)?;
+        file.write_all(bytes)?;
+        Ok(())
+    }
+
+    /// Writes all the messages under a single lock, with a single buffer.
+    ///
+    /// Control characters are handled as in `write`.
+    /// If the write fails midway, a line terminator is appended (best effort)
+    /// so that the partially written line doesn't merge with the next one.
+    fn write_batch(&self, batch: &[(&Record, &str)]) -> Result<(), Error> {
+        let mut buffer = String::new();
+
+        for (_, formatted) in batch {
+            buffer.push_str(&self.clean(formatted));
+            buffer.push('\n');
+        }
+
+        let mut file = self.lock().map_err(|_| Error::Poisoned)?;
+        write_lines(&mut *file, buffer.as_bytes())?;
+        Ok(())
+    }
+
+    /// Flushes the file.
+    fn flush(&self) -> Result<(), Error> {
+        self.lock().map_err(|_| Error::Poisoned)?.flush()?;
+        Ok(())
+    }
+
+    /// Checks that the file still exists and is not read-only.
+    ///
+    /// Files created with `from_file` are only checked for a poisoned lock.
+    fn validate(&self) -> Result<(), Error> {
+        drop(self.lock().map_err(|_| Error::Poisoned)?);
+
+        if let Some(path) = &self.path
+            && fs::metadata(path)?.permissions().readonly()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is read-only", path.display()),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the custom filter level for the file target.
+    /// If the filter level is set, log messages with a lower level
+    /// will be ignored.
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns `true` only if `keep_colors` is set.
+    fn wants_color(&self) -> bool {
+        self.keep_colors
+    }
+
+    /// Returns the maximum level set with `level_range`, if any.
+    fn max_level(&self) -> Option<LogLevel> {
+        self.max_level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    /// Returns the target ID for the file target.
+    /// This is used to identify the target in the logger.
+    ///
+    /// If the file is named, the id is `TargetId::Custom` with the name.
+    fn id(&self) -> TargetId {
+        match (&self.name, &self.path) {
+            (Some(name), _) => TargetId::Custom(name.clone()),
+            (None, Some(path)) => TargetId::File(path.clone()),
+            (None, None) => TargetId::Custom("file".to_string()),
+        }
+    }
+}
+
+/// Writes a buffer of newline terminated lines.
+///
+/// On failure, terminates the last partially written line,
+/// to keep the line framing intact for the writes that follow.
+fn write_lines<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    let mut written = 0;
+
+    let result = loop {
+        if written == bytes.len() {
+            break Ok(());
+        }
+
+        match writer.write(&bytes[written..]) {
+            Ok(0) => break Err(io::Error::from(io::ErrorKind::WriteZero)),
+            Ok(n) => written += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => break Err(e),
+        }
+    };
+
+    if result.is_err() && written > 0 && bytes[written - 1] != b'\n' {
+        let _ = writer.write_all(b"\n");
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writer that accepts a limited number of bytes per write,
+    /// and fails once its capacity is reached.
+    struct Flaky {
+        data: Vec<u8>,
+        capacity: usize,
+    }
+
+    impl Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.data.len() >= self.capacity {
+                // Let the recovery newline through
+                if buf == b"\n" {
+                    self.data.push(b'\n');
+                    return Ok(1);
+                }
+
+                return Err(io::Error::other("disk full"));
+            }
+
+            let n = buf.len().min(4).min(self.capacity - self.data.len());
+            self.data.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_level_range() {
+        let console = Console::new().level_range(LogLevel::Debug, LogLevel::Warn);
+
+        assert!(!console.accepts(LogLevel::Trace));
+        assert!(console.accepts(LogLevel::Debug));
+        assert!(console.accepts(LogLevel::Warn));
+        assert!(!console.accepts(LogLevel::Error));
+
+        let console = Console::new().min_level(LogLevel::Info);
+
+        assert!(!console.accepts(LogLevel::Debug));
+        assert!(console.accepts(LogLevel::Fatal));
+
+        let console = Console::new().only_level(LogLevel::Warn);
+
+        assert!(!console.accepts(LogLevel::Info));
+        assert!(console.accepts(LogLevel::Warn));
+        assert!(!console.accepts(LogLevel::Error));
+
+        let console = Console::new().only_levels(&[LogLevel::Debug, LogLevel::Error]);
+
+        assert!(console.accepts(LogLevel::Debug));
+        assert!(!console.accepts(LogLevel::Warn));
+        assert!(console.accepts(LogLevel::Error));
+    }
+
+    #[test]
+    fn test_stderr_from() {
+        let console = Console::new()
+            .output(Output::Stderr)
+            .stderr_from(LogLevel::Warn)
+            .filtered_output(LogLevel::Fatal, Output::Stdout);
+
+        assert_eq!(console.output_for(LogLevel::Info), Output::Stdout);
+        assert_eq!(console.output_for(LogLevel::Warn), Output::Stderr);
+        assert_eq!(console.output_for(LogLevel::Error), Output::Stderr);
+        assert_eq!(console.output_for(LogLevel::Fatal), Output::Stdout);
+
+        assert_eq!(Console::stderr().output_for(LogLevel::Info), Output::Stderr);
+        assert_eq!(Console::stdout().id(), TargetId::Console(Output::Stdout));
+    }
+
+    #[test]
+    fn test_console_writers() {
+        let stdout = Arc::new(Mutex::new(Vec::new()));
+        let stderr = Arc::new(Mutex::new(Vec::new()));
+
+        let console = Console::new()
+            .stderr_from(LogLevel::Error)
+            .writers(stdout.clone(), stderr.clone());
+
+        console.write(LogLevel::Info, "started").unwrap();
+        console.write(LogLevel::Error, "failed").unwrap();
+
+        assert_eq!(*stdout.lock().unwrap(), b"started\n");
+        assert_eq!(*stderr.lock().unwrap(), b"failed\n");
+    }
+
+    #[test]
+    fn test_console_color_mode() {
+        let writer = Arc::new(Mutex::new(Vec::new()));
+        let console = Console::new().writers(writer.clone(), writer.clone());
+
+        console
+            .write(LogLevel::Info, "\x1b[31mauto\x1b[39m")
+            .unwrap();
+        console
+            .color_mode(ColorMode::Always)
+            .write(LogLevel::Info, "\x1b[31malways\x1b[39m")
+            .unwrap();
+
+        assert_eq!(*writer.lock().unwrap(), b"auto\n\x1b[31malways\x1b[39m\n");
+    }
+
+    #[test]
+    fn test_trailing_newlines() {
+        let writer = Arc::new(Mutex::new(Vec::new()));
+        let console = Console::new().writers(writer.clone(), writer.clone());
+
+        console.write(LogLevel::Info, "one\n").unwrap();
+        console.write(LogLevel::Info, "two\r\n").unwrap();
+        console.write(LogLevel::Info, "three\n\n").unwrap();
+        console
+            .normalize_line_endings()
+            .write(LogLevel::Info, "four\rfive\r\n")
+            .unwrap();
+
+        assert_eq!(*writer.lock().unwrap(), b"one\ntwo\nthree\n\nfour\nfive\n");
+
+        let path =
+            std::env::temp_dir().join(format!("traccia-newlines-{}.log", std::process::id()));
+        let file = File::new(&path, FileMode::Truncate)
+            .unwrap()
+            .normalize_line_endings();
+
+        file.write(LogLevel::Info, "a\r\nb\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a\nb\n");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_target_id_display() {
+        assert_eq!(
+            TargetId::Console(Output::Stderr).to_string(),
+            "console:stderr"
+        );
+        assert_eq!(
+            TargetId::File(PathBuf::from("/var/log/app.log")).to_string(),
+            "file:/var/log/app.log"
+        );
+        assert_eq!(
+            TargetId::Custom("audit".to_string()).to_string(),
+            "custom:audit"
+        );
+    }
+
+    #[test]
+    fn test_named_targets() {
+        assert_eq!(
+            Console::new().named("audit").id(),
+            TargetId::Custom("audit".to_string())
+        );
+
+        assert_eq!(
+            Named::new(Console::new(), "audit").id(),
+            TargetId::Custom("audit".to_string())
+        );
+
+        assert_eq!(Console::new().id(), TargetId::Console(Output::Stdout));
+    }
+
+    #[test]
+    fn test_file_write_bytes() {
+        let path = std::env::temp_dir().join(format!("traccia-bytes-{}.log", std::process::id()));
+        let file = File::new(&path, FileMode::Truncate).unwrap();
+
+        file.write_bytes(LogLevel::Info, &[0, 159, 146, 150, 10])
+            .unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), [0, 159, 146, 150, 10]);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_file_from_file() {
+        let path = std::env::temp_dir().join(format!("traccia-from-{}.log", std::process::id()));
+        let file = File::from_file(fs::File::create(&path).unwrap(), None);
+
+        file.write(LogLevel::Info, "opened elsewhere").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "opened elsewhere\n");
+        assert_eq!(file.id(), TargetId::Custom("file".to_string()));
+        assert_eq!(
+            File::from_file(fs::File::open(&path).unwrap(), Some("app".to_string())).id(),
+            TargetId::Custom("app".to_string())
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        let path =
+            std::env::temp_dir().join(format!("traccia-validate-{}.log", std::process::id()));
+        let file = File::new(&path, FileMode::Truncate).unwrap();
+
+        assert!(file.validate().is_ok());
+        assert!(matches!(
+            crate::Config::default().without_targets().validate(),
+            Err(Error::NoTargets)
+        ));
+
+        fs::remove_file(&path).unwrap();
+
+        let config = crate::Config::default().with_target(file.named("app"));
+
+        match config.validate() {
+            Err(Error::InvalidTarget { id, source }) => {
+                assert_eq!(id, TargetId::Custom("app".to_string()));
+                assert!(matches!(*source, Error::Io(ref e) if e.kind() == io::ErrorKind::NotFound));
+            }
+            other => panic!("expected an invalid target, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_file_header() {
+        let path = std::env::temp_dir().join(format!("traccia-header-{}.log", std::process::id()));
+
+        for run in ["first", "second"] {
+            let file = File::new(&path, FileMode::Append)
+                .unwrap()
+                .with_header(format!("--- {} run ---", run))
+                .unwrap();
+
+            file.write(LogLevel::Info, run).unwrap();
+        }
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "--- first run ---\nfirst\n--- second run ---\nsecond\n"
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_control_chars() {
+        let message = "user\r\n[ERROR] forged\x00\tend";
+
+        assert_eq!(
+            util::sanitize_control_chars(message, ControlChars::Escape),
+            "user\\r\\n[ERROR] forged\\x00\tend"
+        );
+        assert_eq!(
+            util::sanitize_control_chars(message, ControlChars::Strip),
+            "user[ERROR] forged\tend"
+        );
+        assert_eq!(
+            util::sanitize_control_chars(message, ControlChars::Keep),
+            message
+        );
+    }
+
+    #[test]
+    fn test_strip_borrows_plain_text() {
+        assert!(matches!(
+            util::strip_ansi_codes_cow("[INFO] plain"),
+            Cow::Borrowed("[INFO] plain")
+        ));
+        assert!(matches!(
+            util::strip_ansi_codes_cow("\x1b[32m[INFO]\x1b[39m colored"),
+            Cow::Owned(s) if s == "[INFO] colored"
+        ));
+
+        let path = std::env::temp_dir().join(format!("traccia-clean-{}", std::process::id()));
+        let file = File::new(&path, FileMode::Truncate).unwrap();
+
+        assert!(matches!(file.clean("plain\n"), Cow::Borrowed("plain")));
+        // The colors are left to the logger, see `wants_color`
+        assert!(matches!(file.clean("\x1b[1mbold\x1b[0m"), Cow::Borrowed(_)));
+        assert!(!file.wants_color());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_write_lines_keeps_framing_on_failure() {
+        let mut writer = Flaky {
+            data: Vec::new(),
+            capacity: 10,
+        };
+
+        assert!(write_lines(&mut writer, b"first\nsecond\n").is_err());
+        assert_eq!(writer.data, b"first\nseco\n");
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{LogLevel, Record, Target, TargetId, error::Error};
+
+/// Target wrapper that gives a stable name to any target.
+///
+/// The id of the wrapped target becomes `TargetId::Custom(name)`,
+/// which can be matched in hooks across runs, unlike the default id of custom targets.
+/// Everything else is delegated to the wrapped target.
+///
+/// `Console` and `File` have a `named` builder method doing the same.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Hook, Named, TargetId, set_hook};
+///
+/// let audit = Named::new(MyAuditTarget::new(), "audit");
+///
+/// set_hook(Hook::AfterLog(Box::new(|_, target| {
+///     if *target == TargetId::Custom("audit".to_string()) {
+///         // ...
+///     }
+/// })));
+/// ```
+#[derive(Clone)]
+pub struct Named {
+    inner: Box<dyn Target>,
+    name: String,
+}
+
+impl Named {
+    /// Wraps `inner`, giving it the id `TargetId::Custom(name)`.
+    pub fn new<T>(inner: T, name: impl Into<String>) -> Self
+    where
+        T: Target + 'static,
+    {
+        Self {
+            inner: Box::new(inner),
+            name: name.into(),
+        }
+    }
+}
+
+impl Target for Named {
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        self.inner.write(level, formatted)
+    }
+
+    fn write_bytes(&self, level: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        self.inner.write_bytes(level, bytes)
+    }
+
+    fn write_record(&self, record: &Record, formatted: &str) -> Result<(), Error> {
+        self.inner.write_record(record, formatted)
+    }
+
+    fn write_batch(&self, batch: &[(&Record, &str)]) -> Result<(), Error> {
+        self.inner.write_batch(batch)
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        self.inner.flush()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.inner.validate()
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.inner.filter_level()
+    }
+
+    fn max_level(&self) -> Option<LogLevel> {
+        self.inner.max_level()
+    }
+
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.inner.only_levels()
+    }
+
+    fn wants_color(&self) -> bool {
+        self.inner.wants_color()
+    }
+
+    /// Returns `TargetId::Custom` with the given name.
+    fn id(&self) -> TargetId {
+        TargetId::Custom(self.name.clone())
+    }
+}
@@ -0,0 +1,347 @@
+use crate::{LogLevel, Record, Target, TargetId, error::Error, r#impl};
+use std::{
+    io,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// Name of the thread writing to the inner target.
+const WORKER_NAME: &str = "traccia-non-blocking";
+
+/// Default number of messages the queue can hold.
+const DEFAULT_CAPACITY: usize = 10_000;
+
+/// What a [`NonBlocking`] target does with a message when its queue is full.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QueueFull {
+    /// The message is dropped and counted, see [`NonBlocking::dropped`] (default).
+    #[default]
+    Drop,
+
+    /// The caller waits until there is room in the queue.
+    Block,
+}
+
+enum Message {
+    Write(LogLevel, String),
+    Bytes(LogLevel, Vec<u8>),
+    Record(Box<Record>, String),
+    Batch(Vec<(Record, String)>),
+    Flush,
+    Shutdown,
+}
+
+/// Target that writes to another target from a dedicated thread.
+///
+/// Messages are put in a bounded queue and written by the thread, so slow writes,
+/// e.g. to a file on a network drive, don't hold up the caller. This is mostly useful
+/// with the `blocking` feature, where the logger writes on the calling thread.
+///
+/// The thread stops when the [`WorkerGuard`] returned along with the target is dropped,
+/// after writing the queued messages and flushing the inner target. Keep the guard
+/// alive until the end of `main`: messages written after it is dropped are lost.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, File, FileMode, NonBlocking};
+///
+/// fn main() {
+///     let file = File::new("logs/app.log", FileMode::Append).expect("Failed to open log file");
+///     let (file, _guard) = NonBlocking::new(file);
+///
+///     traccia::init_with_config(Config::default().with_target(file));
+///
+///     // ...
+/// }
+/// ```
+#[derive(Clone)]
+pub struct NonBlocking {
+    inner: Arc<dyn Target>,
+    sender: mpsc::SyncSender<Message>,
+    queue_full: QueueFull,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Guard stopping the thread of a [`NonBlocking`] target when dropped,
+/// once the queued messages are written.
+#[must_use = "the writer thread stops as soon as the guard is dropped"]
+pub struct WorkerGuard {
+    sender: mpsc::SyncSender<Message>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for WorkerGuard {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Message::Shutdown);
+
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl NonBlocking {
+    /// Wraps a target, writing to it from a dedicated thread.
+    ///
+    /// The queue holds up to 10 000 messages, further messages are dropped.
+    ///
+    /// # Returns
+    ///
+    /// The target, and the guard stopping the thread when dropped
+    pub fn new(inner: impl Target + 'static) -> (Self, WorkerGuard) {
+        Self::with_capacity(inner, DEFAULT_CAPACITY, QueueFull::Drop)
+    }
+
+    /// Wraps a target, writing to it from a dedicated thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The target to write to
+    /// * `capacity` - Number of messages the queue can hold
+    /// * `queue_full` - What to do with a message when the queue is full
+    ///
+    /// # Returns
+    ///
+    /// The target, and the guard stopping the thread when dropped
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread can't be spawned
+    pub fn with_capacity(
+        inner: impl Target + 'static,
+        capacity: usize,
+        queue_full: QueueFull,
+    ) -> (Self, WorkerGuard) {
+        let inner: Arc<dyn Target> = Arc::new(inner);
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+
+        let worker_inner = Arc::clone(&inner);
+        let worker = thread::Builder::new()
+            .name(WORKER_NAME.to_string())
+            .spawn(move || Self::worker_thread(receiver, worker_inner.as_ref()))
+            .expect("Failed to spawn the non-blocking writer thread");
+
+        let target = Self {
+            inner,
+            sender: sender.clone(),
+            queue_full,
+            dropped: Arc::new(AtomicU64::new(0)),
+        };
+
+        let guard = WorkerGuard {
+            sender,
+            worker: Some(worker),
+        };
+
+        (target, guard)
+    }
+
+    /// Returns how many messages have been dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn worker_thread(receiver: mpsc::Receiver<Message>, inner: &dyn Target) {
+        for message in receiver {
+            let result = match message {
+                Message::Write(level, formatted) => inner.write(level, &formatted),
+                Message::Bytes(level, bytes) => inner.write_bytes(level, &bytes),
+                Message::Record(record, formatted) => inner.write_record(&record, &formatted),
+                Message::Batch(batch) => inner.write_batch(
+                    &batch
+                        .iter()
+                        .map(|(record, formatted)| (record, formatted.as_str()))
+                        .collect::<Vec<_>>(),
+                ),
+                Message::Flush => inner.flush(),
+                Message::Shutdown => break,
+            };
+
+            if let Err(e) = result {
+                r#impl::report_write_error(inner, e);
+            }
+        }
+
+        if let Err(e) = inner.flush() {
+            r#impl::report_write_error(inner, e);
+        }
+    }
+
+    fn send(&self, message: Message) -> Result<(), Error> {
+        let result = match self.queue_full {
+            QueueFull::Block => self.sender.send(message).map_err(|_| ()),
+            QueueFull::Drop => match self.sender.try_send(message) {
+                Err(mpsc::TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    Ok(())
+                }
+                result => result.map_err(|_| ()),
+            },
+        };
+
+        result.map_err(|_| io::Error::other("the non-blocking writer thread has stopped").into())
+    }
+}
+
+impl Target for NonBlocking {
+    /// Queues the message for the inner target.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        self.send(Message::Write(level, formatted.to_string()))
+    }
+
+    /// Queues the bytes for the inner target.
+    fn write_bytes(&self, level: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        self.send(Message::Bytes(level, bytes.to_vec()))
+    }
+
+    /// Queues the record for the inner target.
+    fn write_record(&self, record: &Record, formatted: &str) -> Result<(), Error> {
+        self.send(Message::Record(
+            Box::new(record.clone()),
+            formatted.to_string(),
+        ))
+    }
+
+    /// Queues the whole batch as a single message.
+    fn write_batch(&self, batch: &[(&Record, &str)]) -> Result<(), Error> {
+        self.send(Message::Batch(
+            batch
+                .iter()
+                .map(|(record, formatted)| ((*record).clone(), formatted.to_string()))
+                .collect(),
+        ))
+    }
+
+    /// Queues a flush of the inner target, after the messages queued so far,
+    /// without waiting for it: the loggers flush periodically, and waiting would stall them
+    /// on the inner target. Dropping the [`WorkerGuard`] waits for the queued messages instead.
+    ///
+    /// When the queue is full, the flush is skipped, or waits for room with `QueueFull::Block`.
+    /// Once the thread has stopped, there is nothing left to flush.
+    fn flush(&self) -> Result<(), Error> {
+        match self.queue_full {
+            QueueFull::Block => {
+                let _ = self.sender.send(Message::Flush);
+            }
+            QueueFull::Drop => {
+                let _ = self.sender.try_send(Message::Flush);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.inner.validate()
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.inner.filter_level()
+    }
+
+    fn max_level(&self) -> Option<LogLevel> {
+        self.inner.max_level()
+    }
+
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.inner.only_levels()
+    }
+
+    fn wants_color(&self) -> bool {
+        self.inner.wants_color()
+    }
+
+    /// Returns the id of the inner target.
+    fn id(&self) -> TargetId {
+        self.inner.id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryTarget;
+    use std::sync::Mutex;
+
+    /// Target waiting for a lock before each write.
+    #[derive(Clone)]
+    struct Gated {
+        gate: Arc<Mutex<()>>,
+        inner: MemoryTarget,
+    }
+
+    impl Target for Gated {
+        fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+            let _open = self.gate.lock().unwrap();
+            self.inner.write(level, formatted)
+        }
+    }
+
+    #[test]
+    fn test_written_before_guard_dropped() {
+        let memory = MemoryTarget::new();
+        let (target, guard) = NonBlocking::with_capacity(memory.clone(), 1, QueueFull::Block);
+
+        for i in 0..10 {
+            target.write(LogLevel::Info, &i.to_string()).unwrap();
+        }
+
+        target.flush().unwrap();
+        target.write(LogLevel::Info, "last").unwrap();
+        drop(guard);
+
+        assert_eq!(memory.len(), 11);
+        assert!(target.write(LogLevel::Info, "lost").is_err());
+        assert!(target.flush().is_ok());
+    }
+
+    #[test]
+    fn test_dropped_when_full() {
+        let gate = Arc::new(Mutex::new(()));
+        let memory = MemoryTarget::new();
+        let closed = gate.lock().unwrap();
+
+        let (target, guard) = NonBlocking::with_capacity(
+            Gated {
+                gate: Arc::clone(&gate),
+                inner: memory.clone(),
+            },
+            1,
+            QueueFull::Drop,
+        );
+
+        target.write(LogLevel::Info, "blocked").unwrap();
+
+        // Wait for the worker to take the first message and block on the gate
+        while target
+            .sender
+            .try_send(Message::Write(LogLevel::Info, "queued".into()))
+            .is_err()
+        {
+            thread::yield_now();
+        }
+
+        target.write(LogLevel::Info, "dropped").unwrap();
+        assert_eq!(target.dropped(), 1);
+
+        // Doesn't wait for the worker, nor count as a dropped message
+        target.flush().unwrap();
+        assert_eq!(target.dropped(), 1);
+
+        drop(closed);
+        drop(guard);
+
+        assert_eq!(
+            memory.drain(),
+            [
+                (LogLevel::Info, "blocked".to_string()),
+                (LogLevel::Info, "queued".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,504 @@
+use crate::{LogLevel, Record, Target, TargetId, error::Error, util};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Port used by OTLP collectors for HTTP.
+const DEFAULT_PORT: u16 = 4318;
+
+/// Path of the logs endpoint of OTLP collectors.
+const LOGS_PATH: &str = "/v1/logs";
+
+/// Timeout for connecting to the collector and for each request.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Target that exports records to an OpenTelemetry collector,
+/// as OTLP log records over HTTP, with the JSON encoding.
+///
+/// Each call sends a single request, so with the async logger every batch
+/// of records is exported at once, from the worker thread.
+///
+/// The levels are mapped to OpenTelemetry severity numbers (`TRACE` = 1, `DEBUG` = 5,
+/// `INFO` = 9, `WARN` = 13, `ERROR` = 17, `FATAL` = 21). Custom levels rank right above
+/// the built-in level with the same severity, e.g. 10 for a severity of 2.
+///
+/// The body of each log record is the raw record message. The source location
+/// is sent as the `code.*` attributes, the backtrace as `exception.stacktrace`,
+/// and span fields as `<span>.<key>` attributes, the innermost span taking precedence.
+/// A trace id made of 32 hex digits is sent as the `traceId` of the log record,
+/// any other trace id as the `trace_id` attribute.
+///
+/// Only plain HTTP is supported, as TLS would require a dependency:
+/// export to a local collector or agent, which can forward over TLS.
+/// gRPC is not supported either.
+///
+/// Only available with the `otlp` feature.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, OtlpTarget};
+///
+/// let otlp = OtlpTarget::new("http://localhost:4318")
+///     .expect("Invalid endpoint")
+///     .service_name("billing");
+///
+/// let config = Config::default().with_target(otlp);
+///
+/// config.validate().expect("The collector is unreachable");
+/// traccia::init_with_config(config);
+/// ```
+#[derive(Debug, Clone)]
+pub struct OtlpTarget {
+    host: String,
+    port: u16,
+    path: String,
+    headers: Vec<(String, String)>,
+    service_name: Option<String>,
+    timeout: Duration,
+    level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+}
+
+impl OtlpTarget {
+    /// Creates a target exporting to the collector at `endpoint`.
+    ///
+    /// The endpoint is an `http://` URL. The port defaults to 4318, and the path
+    /// to `/v1/logs`, so `http://localhost` is the same as `http://localhost:4318/v1/logs`.
+    ///
+    /// No connection is made until the first export,
+    /// use [`Config::validate`](crate::Config::validate) to check that the collector is reachable.
+    ///
+    /// # Returns
+    ///
+    /// A new `OtlpTarget`, or an error if the endpoint is not a valid `http://` URL
+    pub fn new(endpoint: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| -> Error {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid OTLP endpoint {}: {}", endpoint, reason),
+            )
+            .into()
+        };
+
+        let rest = endpoint
+            .strip_prefix("http://")
+            .ok_or_else(|| invalid("only http:// is supported"))?;
+
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| invalid("the port is not a number"))?,
+            ),
+            None => (authority, DEFAULT_PORT),
+        };
+
+        if host.is_empty() {
+            return Err(invalid("missing host"));
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: match path {
+                "" | "/" => LOGS_PATH.to_string(),
+                path => path.to_string(),
+            },
+            headers: Vec::new(),
+            service_name: None,
+            timeout: DEFAULT_TIMEOUT,
+            level: None,
+            only_levels: None,
+        })
+    }
+
+    /// Builder method to set the `service.name` resource attribute.
+    pub fn service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_name = Some(name.into());
+        self
+    }
+
+    /// Builder method to add a header to the requests, e.g. for authentication.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Builder method to set the timeout for connecting and for each request (default: 5 seconds).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`](crate::File::only_level).
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+
+    fn connect(&self) -> Result<TcpStream, Error> {
+        let addr: SocketAddr = (self.host.as_str(), self.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("could not resolve {}", self.host),
+                )
+            })?;
+
+        let stream = TcpStream::connect_timeout(&addr, self.timeout)?;
+
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        Ok(stream)
+    }
+
+    /// Sends the records in a single request.
+    fn export(&self, records: &[&Record]) -> Result<(), Error> {
+        let body = self.body(records, SystemTime::now());
+        let mut stream = self.connect()?;
+
+        let mut request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.path,
+            self.host,
+            self.port,
+            body.len()
+        );
+
+        for (name, value) in &self.headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+
+        request.push_str("\r\n");
+        request.push_str(&body);
+
+        stream.write_all(request.as_bytes())?;
+
+        let mut status = String::new();
+        BufReader::new(stream).read_line(&mut status)?;
+
+        match status.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(()),
+            _ => Err(io::Error::other(format!(
+                "the OTLP collector responded with {:?}",
+                status.trim()
+            ))
+            .into()),
+        }
+    }
+
+    /// Encodes the records as an OTLP `ExportLogsServiceRequest`.
+    fn body(&self, records: &[&Record], observed: SystemTime) -> String {
+        let observed = observed
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+
+        let records = records
+            .iter()
+            .map(|record| log_record(record, observed))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let resource = match &self.service_name {
+            Some(name) => attributes(&[("service.name".to_string(), name.clone())]),
+            None => "[]".to_string(),
+        };
+
+        format!(
+            r#"{{"resourceLogs":[{{"resource":{{"attributes":{}}},"scopeLogs":[{{"scope":{{"name":"traccia","version":"{}"}},"logRecords":[{}]}}]}}]}}"#,
+            resource,
+            env!("CARGO_PKG_VERSION"),
+            records
+        )
+    }
+}
+
+/// Maps a level to an OpenTelemetry severity number.
+fn severity_number(level: LogLevel) -> u8 {
+    match level {
+        LogLevel::Custom { severity, .. } => severity.saturating_mul(4).saturating_add(2).min(24),
+        level => level.severity() * 4 + 1,
+    }
+}
+
+/// Encodes key-value pairs as OTLP string attributes.
+fn attributes(pairs: &[(String, String)]) -> String {
+    let attributes = pairs
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                r#"{{"key":{},"value":{{"stringValue":{}}}}}"#,
+                util::json_string(key),
+                util::json_string(value)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!("[{}]", attributes)
+}
+
+/// Encodes a record as an OTLP log record.
+fn log_record(record: &Record, observed: u128) -> String {
+    let mut pairs: Vec<(String, String)> =
+        vec![("traccia.target".to_string(), record.target.clone())];
+
+    if let Some(module_path) = record.module_path {
+        pairs.push(("code.namespace".to_string(), module_path.to_string()));
+    }
+
+    if let Some(file) = record.file {
+        pairs.push(("code.filepath".to_string(), file.to_string()));
+    }
+
+    if let Some(line) = record.line {
+        pairs.push(("code.lineno".to_string(), line.to_string()));
+    }
+
+    if let Some(backtrace) = &record.backtrace {
+        pairs.push(("exception.stacktrace".to_string(), backtrace.to_string()));
+    }
+
+    let trace_id = match &record.trace_id {
+        Some(id) if id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            format!(
+                r#""traceId":{},"#,
+                util::json_string(&id.to_ascii_lowercase())
+            )
+        }
+        Some(id) => {
+            pairs.push(("trace_id".to_string(), id.clone()));
+            String::new()
+        }
+        None => String::new(),
+    };
+
+    for span in &record.context {
+        for (key, value) in &span.fields {
+            let key = format!("{}.{}", span.name, key);
+
+            match pairs.iter_mut().find(|(k, _)| *k == key) {
+                Some(pair) => pair.1 = value.clone(),
+                None => pairs.push((key, value.clone())),
+            }
+        }
+    }
+
+    format!(
+        r#"{{"observedTimeUnixNano":"{}",{}"severityNumber":{},"severityText":{},"body":{{"stringValue":{}}},"attributes":{}}}"#,
+        observed,
+        trace_id,
+        severity_number(record.level),
+        util::json_string(&record.level.to_string()),
+        util::json_string(&record.message),
+        attributes(&pairs)
+    )
+}
+
+impl Target for OtlpTarget {
+    /// Exports a plain message, with the given level.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        self.export(&[&Record::builder(level, formatted).build()])
+    }
+
+    /// Exports the record. The formatted message is ignored,
+    /// as the fields of the record are sent separately.
+    fn write_record(&self, record: &Record, _: &str) -> Result<(), Error> {
+        self.export(&[record])
+    }
+
+    /// Exports all the records in a single request.
+    fn write_batch(&self, batch: &[(&Record, &str)]) -> Result<(), Error> {
+        let records = batch.iter().map(|(record, _)| *record).collect::<Vec<_>>();
+        self.export(&records)
+    }
+
+    /// Checks that the collector accepts connections.
+    fn validate(&self) -> Result<(), Error> {
+        self.connect().map(drop)
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    /// Returns `false`, the messages are sent without colors.
+    fn wants_color(&self) -> bool {
+        false
+    }
+
+    fn id(&self) -> TargetId {
+        TargetId::Custom("otlp".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Span;
+    use std::{io::Read, net::TcpListener, thread};
+
+    /// Accepts a single request, answering with `status`, and returns the request.
+    fn collector(status: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut length = 0;
+
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+
+                if let Some(value) = line.strip_prefix("Content-Length: ") {
+                    length = value.trim().parse().unwrap();
+                }
+
+                request.push_str(&line);
+
+                if line == "\r\n" {
+                    break;
+                }
+            }
+
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+
+            write!(stream, "HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status).unwrap();
+            request
+        });
+
+        (endpoint, handle)
+    }
+
+    #[test]
+    fn test_endpoint() {
+        let otlp = OtlpTarget::new("http://collector").unwrap();
+        assert_eq!((otlp.host.as_str(), otlp.port), ("collector", 4318));
+        assert_eq!(otlp.path, "/v1/logs");
+
+        let otlp = OtlpTarget::new("http://10.0.0.1:9000/otlp/v1/logs").unwrap();
+        assert_eq!((otlp.host.as_str(), otlp.port), ("10.0.0.1", 9000));
+        assert_eq!(otlp.path, "/otlp/v1/logs");
+
+        assert!(OtlpTarget::new("https://collector").is_err());
+        assert!(OtlpTarget::new("http://:4318").is_err());
+        assert!(OtlpTarget::new("http://collector:port").is_err());
+    }
+
+    #[test]
+    fn test_severity_number() {
+        assert_eq!(severity_number(LogLevel::Trace), 1);
+        assert_eq!(severity_number(LogLevel::Info), 9);
+        assert_eq!(severity_number(LogLevel::Fatal), 21);
+        assert_eq!(severity_number(LogLevel::custom("NOTICE", 2)), 10);
+        assert_eq!(severity_number(LogLevel::custom("AUDIT", 200)), 24);
+    }
+
+    #[test]
+    fn test_log_record() {
+        let record = Record::builder(LogLevel::Warn, "low \"disk\"")
+            .target("app")
+            .line(7)
+            .context(vec![
+                Span::new("request", vec![("user".to_string(), "42".to_string())]),
+                Span::new("request", vec![("user".to_string(), "7".to_string())]),
+            ])
+            .build();
+
+        assert_eq!(
+            log_record(&record, 1),
+            concat!(
+                r#"{"observedTimeUnixNano":"1","severityNumber":13,"severityText":"WARN","#,
+                r#""body":{"stringValue":"low \"disk\""},"attributes":["#,
+                r#"{"key":"traccia.target","value":{"stringValue":"app"}},"#,
+                r#"{"key":"code.lineno","value":{"stringValue":"7"}},"#,
+                r#"{"key":"request.user","value":{"stringValue":"7"}}]}"#
+            )
+        );
+
+        let record = Record::builder(LogLevel::Info, "hi")
+            .trace_id("4BF92F3577B34DA6A3CE929D0E0E4736")
+            .build();
+
+        assert!(log_record(&record, 1).starts_with(
+            r#"{"observedTimeUnixNano":"1","traceId":"4bf92f3577b34da6a3ce929d0e0e4736","#
+        ));
+
+        let record = Record::builder(LogLevel::Info, "hi")
+            .trace_id("req-1")
+            .build();
+
+        assert!(
+            log_record(&record, 1)
+                .ends_with(r#"{"key":"trace_id","value":{"stringValue":"req-1"}}]}"#)
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let (endpoint, collector) = collector("200 OK");
+        let otlp = OtlpTarget::new(&endpoint)
+            .unwrap()
+            .service_name("billing")
+            .header("Authorization", "Bearer token");
+
+        let first = Record::builder(LogLevel::Info, "first").build();
+        let second = Record::builder(LogLevel::Error, "second").build();
+
+        otlp.write_batch(&[(&first, "first"), (&second, "second")])
+            .unwrap();
+
+        let request = collector.join().unwrap();
+
+        assert!(request.starts_with("POST /v1/logs HTTP/1.1\r\n"));
+        assert!(request.contains("Authorization: Bearer token\r\n"));
+        assert!(request.contains(r#"{"key":"service.name","value":{"stringValue":"billing"}}"#));
+        assert!(request.contains(r#""body":{"stringValue":"first"}"#));
+        assert!(request.contains(r#""body":{"stringValue":"second"}"#));
+    }
+
+    #[test]
+    fn test_export_rejected() {
+        let (endpoint, collector) = collector("400 Bad Request");
+        let otlp = OtlpTarget::new(&endpoint).unwrap();
+
+        assert!(otlp.write(LogLevel::Info, "rejected").is_err());
+
+        collector.join().unwrap();
+    }
+}
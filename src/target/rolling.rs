@@ -0,0 +1,345 @@
+use crate::{File, FileMode, LogLevel, Record, Target, TargetId, error::Error, util};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How often a [`RollingFile`] starts a new file.
+///
+/// Periods are computed in UTC, not in local time: a daily file starts
+/// at midnight UTC, whatever the time zone of the host. This keeps the boundaries
+/// unaffected by daylight saving time transitions.
+///
+/// The period also sets the suffix added to the file name,
+/// shown here for `app.log`. The dates and hours of the suffix are in UTC as well.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RotationPeriod {
+    /// A new file every hour, at the start of the UTC hour.
+    /// Example: `app-2024-01-02T15.log`
+    HourlyUtc,
+
+    /// A new file every day at midnight UTC (default).
+    /// Example: `app-2024-01-02.log`
+    #[default]
+    DailyUtc,
+
+    /// A new file every ISO week, starting on Monday at midnight UTC.
+    /// Example: `app-2024-W01.log`
+    WeeklyUtc,
+}
+
+impl RotationPeriod {
+    /// Returns the suffix of the period containing `time`.
+    ///
+    /// Two points in time belong to the same period if and only if they have the same suffix.
+    fn suffix(self, time: SystemTime) -> String {
+        let secs = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let days = (secs / 86_400) as i64;
+        let (year, month, day) = util::civil_from_days(days);
+
+        match self {
+            RotationPeriod::HourlyUtc => format!(
+                "{:04}-{:02}-{:02}T{:02}",
+                year,
+                month,
+                day,
+                secs % 86_400 / 3_600
+            ),
+            RotationPeriod::DailyUtc => format!("{:04}-{:02}-{:02}", year, month, day),
+            RotationPeriod::WeeklyUtc => {
+                // The ISO week belongs to the year of its Thursday.
+                // The epoch was a Thursday, so Monday is 0.
+                let weekday = (days + 3).rem_euclid(7);
+                let thursday = days - weekday + 3;
+                let (week_year, _, _) = util::civil_from_days(thursday);
+                let week = (thursday - util::days_from_civil(week_year, 1, 1)) / 7 + 1;
+
+                format!("{:04}-W{:02}", week_year, week)
+            }
+        }
+    }
+}
+
+/// Returns the path of the file for a period suffix,
+/// inserting the suffix before the extension.
+fn path(dir: &Path, file_name: &Path, suffix: &str) -> PathBuf {
+    let stem = file_name
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+
+    let name = match file_name.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, suffix, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, suffix),
+    };
+
+    dir.join(name)
+}
+
+/// Target that writes to a new file at each period, e.g. one file per day.
+///
+/// The files are named after the given file name, with the period inserted
+/// before the extension: `app.log` becomes `app-2024-01-02.log` with daily rotation.
+/// Files are opened in append mode, so restarting the application
+/// within a period keeps writing to the same file.
+///
+/// Periods are computed in UTC only, see [`RotationPeriod`]: daily files start
+/// at midnight UTC, not at local midnight.
+///
+/// Messages are written as with [`File`], stripping the ANSI color codes.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, RollingFile, RotationPeriod};
+///
+/// let rolling = RollingFile::new("logs", "app.log", RotationPeriod::HourlyUtc)
+///     .expect("Failed to open log file");
+///
+/// let config = Config {
+///     targets: vec![Box::new(rolling)],
+///     ..Config::default()
+/// };
+/// ```
+#[derive(Clone)]
+pub struct RollingFile {
+    dir: PathBuf,
+    file_name: PathBuf,
+    period: RotationPeriod,
+    current: Arc<Mutex<(String, File)>>,
+    header: Option<Arc<dyn Fn() -> String + Send + Sync>>,
+    level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+}
+
+impl RollingFile {
+    /// Creates a rolling file target, opening the file of the current period.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory containing the log files, created if it doesn't exist
+    /// * `file_name` - Name of the files, before adding the period
+    /// * `period` - How often a new file is started
+    ///
+    /// # Returns
+    ///
+    /// A new `RollingFile` target or an error if the file couldn't be opened
+    pub fn new<P, N>(dir: P, file_name: N, period: RotationPeriod) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        N: AsRef<Path>,
+    {
+        let dir = dir.as_ref().to_path_buf();
+        let file_name = file_name.as_ref().to_path_buf();
+        let suffix = period.suffix(SystemTime::now());
+        let file = File::new(path(&dir, &file_name, &suffix), FileMode::Append)?;
+
+        Ok(Self {
+            dir,
+            file_name,
+            period,
+            current: Arc::new(Mutex::new((suffix, file))),
+            header: None,
+            level: None,
+            only_levels: None,
+        })
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`](crate::File::only_level).
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+
+    /// Builder method to write a header line at the start of each file,
+    /// as with [`File::with_header`].
+    ///
+    /// The header is written right away to the current file, then each time
+    /// a new file is started. It is produced by a closure, so it can contain
+    /// the time of the rotation.
+    ///
+    /// # Returns
+    ///
+    /// The rolling file target, or an error if the header couldn't be written
+    pub fn with_header<F>(mut self, header: F) -> Result<Self, Error>
+    where
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        {
+            let mut current = self.current.lock().map_err(|_| Error::Poisoned)?;
+            current.1 = current.1.clone().with_header(header())?;
+        }
+
+        self.header = Some(Arc::new(header));
+        Ok(self)
+    }
+
+    /// Runs `f` with the file of the current period, rotating it if the period changed.
+    fn with_file<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&File) -> Result<(), Error>,
+    {
+        let mut current = self.current.lock().map_err(|_| Error::Poisoned)?;
+        let suffix = self.period.suffix(SystemTime::now());
+
+        if current.0 != suffix {
+            let mut file = File::new(path(&self.dir, &self.file_name, &suffix), FileMode::Append)?;
+
+            if let Some(header) = &self.header {
+                file = file.with_header(header())?;
+            }
+
+            current.1.flush()?;
+            *current = (suffix, file);
+        }
+
+        f(&current.1)
+    }
+}
+
+impl Target for RollingFile {
+    /// Writes the formatted log message to the file of the current period.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        self.with_file(|file| file.write(level, formatted))
+    }
+
+    /// Writes the bytes as they are to the file of the current period.
+    fn write_bytes(&self, level: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        self.with_file(|file| file.write_bytes(level, bytes))
+    }
+
+    /// Writes the record to the file of the current period.
+    fn write_record(&self, record: &Record, formatted: &str) -> Result<(), Error> {
+        self.with_file(|file| file.write_record(record, formatted))
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        let current = self.current.lock().map_err(|_| Error::Poisoned)?;
+        current.1.flush()
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    /// Validates the file of the current period.
+    ///
+    /// The file of the next period is created in the same directory,
+    /// so this also catches a removed directory.
+    fn validate(&self) -> Result<(), Error> {
+        self.current
+            .lock()
+            .map_err(|_| Error::Poisoned)?
+            .1
+            .validate()
+    }
+
+    /// Returns `false`, the files strip the colors.
+    fn wants_color(&self) -> bool {
+        false
+    }
+
+    /// Returns the target ID, made of the file path before adding the period.
+    fn id(&self) -> TargetId {
+        TargetId::File(self.dir.join(&self.file_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, time::Duration};
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_suffixes() {
+        // 2024-01-02T15:04:05Z
+        let time = at(1_704_207_845);
+
+        assert_eq!(RotationPeriod::HourlyUtc.suffix(time), "2024-01-02T15");
+        assert_eq!(RotationPeriod::DailyUtc.suffix(time), "2024-01-02");
+        assert_eq!(RotationPeriod::WeeklyUtc.suffix(time), "2024-W01");
+    }
+
+    #[test]
+    fn test_iso_weeks_across_years() {
+        // Friday 2021-01-01 belongs to the last week of 2020
+        assert_eq!(
+            RotationPeriod::WeeklyUtc.suffix(at(1_609_459_200)),
+            "2020-W53"
+        );
+        // Monday 2024-12-30 belongs to the first week of 2025
+        assert_eq!(
+            RotationPeriod::WeeklyUtc.suffix(at(1_735_516_800)),
+            "2025-W01"
+        );
+        // Sunday 2024-12-29 is still in the last week of 2024
+        assert_eq!(
+            RotationPeriod::WeeklyUtc.suffix(at(1_735_430_400)),
+            "2024-W52"
+        );
+    }
+
+    #[test]
+    fn test_file_names() {
+        let dir = Path::new("logs");
+
+        assert_eq!(
+            path(dir, Path::new("app.log"), "2024-W01"),
+            dir.join("app-2024-W01.log")
+        );
+        assert_eq!(
+            path(dir, Path::new("app"), "2024-01-02"),
+            dir.join("app-2024-01-02")
+        );
+    }
+
+    #[test]
+    fn test_writes_to_current_period() {
+        let dir = std::env::temp_dir().join(format!("traccia-rolling-{}", std::process::id()));
+        let rolling = RollingFile::new(&dir, "app.log", RotationPeriod::DailyUtc).unwrap();
+
+        rolling.write(LogLevel::Info, "started").unwrap();
+
+        let suffix = RotationPeriod::DailyUtc.suffix(SystemTime::now());
+        let written = fs::read_to_string(path(&dir, Path::new("app.log"), &suffix)).unwrap();
+
+        assert_eq!(written, "started\n");
+
+        let rolling = rolling
+            .with_header(|| "--- restarted ---".to_string())
+            .unwrap();
+        rolling.write(LogLevel::Info, "again").unwrap();
+
+        let written = fs::read_to_string(path(&dir, Path::new("app.log"), &suffix)).unwrap();
+
+        assert_eq!(written, "started\n--- restarted ---\nagain\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
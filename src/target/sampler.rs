@@ -0,0 +1,206 @@
+use crate::{LogLevel, Record, Target, TargetId, error::Error};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// Target wrapper that only lets 1 in N records through.
+///
+/// Sampling is deterministic: the first matching record is written,
+/// then the next `n - 1` are dropped, and so on.
+/// By default every level is sampled, use [`Sampler::levels`]
+/// to only sample some of them and always write the others.
+///
+/// Clones share the same counters, so keeping a clone of the sampler
+/// before adding it to the config allows reading [`Sampler::sampled_out`] later.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, Console, LogLevel, Sampler};
+///
+/// // Keep 1 debug record every 100, write everything else.
+/// let sampler = Sampler::new(Console::new(), 100).levels(&[LogLevel::Debug]);
+///
+/// let config = Config {
+///     level: LogLevel::Debug,
+///     targets: vec![Box::new(sampler.clone())],
+///     ..Config::default()
+/// };
+///
+/// // Later on
+/// println!("{} debug records sampled out", sampler.sampled_out());
+/// ```
+#[derive(Clone)]
+pub struct Sampler {
+    inner: Box<dyn Target>,
+    rate: u64,
+    levels: Option<Vec<LogLevel>>,
+    state: Arc<SamplerState>,
+}
+
+#[derive(Default)]
+struct SamplerState {
+    seen: AtomicU64,
+    sampled_out: AtomicU64,
+}
+
+impl Sampler {
+    /// Creates a sampler that writes 1 in `rate` records to `inner`.
+    ///
+    /// A rate of `0` or `1` lets every record through.
+    pub fn new<T>(inner: T, rate: u64) -> Self
+    where
+        T: Target + 'static,
+    {
+        Self {
+            inner: Box::new(inner),
+            rate: rate.max(1),
+            levels: None,
+            state: Arc::new(SamplerState::default()),
+        }
+    }
+
+    /// Builder method to restrict sampling to the given levels.
+    ///
+    /// Records with a level not in the list are always written.
+    pub fn levels(mut self, levels: &[LogLevel]) -> Self {
+        self.levels = Some(levels.to_vec());
+        self
+    }
+
+    /// Returns how many records have been dropped by the sampler.
+    pub fn sampled_out(&self) -> u64 {
+        self.state.sampled_out.load(Ordering::Relaxed)
+    }
+
+    fn samples(&self, level: LogLevel) -> bool {
+        self.levels
+            .as_ref()
+            .is_none_or(|levels| levels.contains(&level))
+    }
+
+    /// Counts a record with the given level, returning `true` if it must be dropped.
+    fn sampled_out_now(&self, level: LogLevel) -> bool {
+        if !self.samples(level) {
+            return false;
+        }
+
+        let seen = self.state.seen.fetch_add(1, Ordering::Relaxed);
+
+        if seen.is_multiple_of(self.rate) {
+            return false;
+        }
+
+        self.state.sampled_out.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+}
+
+impl Target for Sampler {
+    /// Writes the message to the inner target if it is not sampled out.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        if self.sampled_out_now(level) {
+            return Ok(());
+        }
+
+        self.inner.write(level, formatted)
+    }
+
+    /// Writes the bytes to the inner target if they are not sampled out.
+    fn write_bytes(&self, level: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        if self.sampled_out_now(level) {
+            return Ok(());
+        }
+
+        self.inner.write_bytes(level, bytes)
+    }
+
+    /// Writes the record to the inner target if it is not sampled out.
+    fn write_record(&self, record: &Record, formatted: &str) -> Result<(), Error> {
+        if self.sampled_out_now(record.level) {
+            return Ok(());
+        }
+
+        self.inner.write_record(record, formatted)
+    }
+
+    /// Flushes the inner target.
+    fn flush(&self) -> Result<(), Error> {
+        self.inner.flush()
+    }
+
+    /// Validates the inner target.
+    fn validate(&self) -> Result<(), Error> {
+        self.inner.validate()
+    }
+
+    /// Returns the filter level of the inner target.
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.inner.filter_level()
+    }
+
+    /// Returns the maximum level of the inner target.
+    fn max_level(&self) -> Option<LogLevel> {
+        self.inner.max_level()
+    }
+
+    /// Returns the only levels accepted by the inner target.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.inner.only_levels()
+    }
+
+    fn wants_color(&self) -> bool {
+        self.inner.wants_color()
+    }
+
+    /// Returns the id of the inner target.
+    fn id(&self) -> TargetId {
+        self.inner.id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    impl Target for Capture {
+        fn write(&self, _: LogLevel, formatted: &str) -> Result<(), Error> {
+            self.0.lock().unwrap().push(formatted.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sampler_keeps_one_in_n() {
+        let capture = Capture::default();
+        let sampler = Sampler::new(capture.clone(), 3);
+
+        for i in 0..10 {
+            sampler.write(LogLevel::Info, &i.to_string()).unwrap();
+        }
+
+        assert_eq!(*capture.0.lock().unwrap(), ["0", "3", "6", "9"]);
+        assert_eq!(sampler.sampled_out(), 6);
+    }
+
+    #[test]
+    fn test_sampler_only_samples_selected_levels() {
+        let capture = Capture::default();
+        let sampler = Sampler::new(capture.clone(), 2).levels(&[LogLevel::Debug]);
+
+        for _ in 0..4 {
+            sampler.write(LogLevel::Debug, "debug").unwrap();
+            sampler.write(LogLevel::Error, "error").unwrap();
+        }
+
+        let lines = capture.0.lock().unwrap();
+        assert_eq!(lines.iter().filter(|l| *l == "debug").count(), 2);
+        assert_eq!(lines.iter().filter(|l| *l == "error").count(), 4);
+        assert_eq!(sampler.clone().sampled_out(), 2);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::{File, FileMode, LogLevel, Record, Target, TargetId, error::Error};
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    thread::{self, ThreadId},
+};
+
+/// How a [`ShardedFile`] picks the file each record is written to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Sharding {
+    /// Each record goes to the next file, in turn (default).
+    #[default]
+    RoundRobin,
+
+    /// Records go to a file chosen from the id of the thread that logged them,
+    /// so the records of a thread stay in order in a single file.
+    Thread,
+}
+
+/// Target that spreads records across several files, each with its own lock,
+/// so concurrent writers don't all wait on the same file.
+///
+/// The files are named after the given path with the shard number before
+/// the extension: `logs/app.log` with 3 shards opens `logs/app.0.log`,
+/// `logs/app.1.log` and `logs/app.2.log`.
+///
+/// This helps when records are written from many threads at once, as with the
+/// `blocking` feature, where each thread writes to the targets itself.
+/// The async logger writes from a single thread, so it rarely benefits from it.
+///
+/// Lines of different files are not ordered with respect to each other.
+/// Use [`ShardedFile::with_sequence`] to prefix each line with a global sequence number,
+/// to merge the files back in order when reading them.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, FileMode, ShardedFile, Sharding};
+///
+/// let sharded = ShardedFile::new("logs/app.log", 4, FileMode::Append)
+///     .expect("Failed to open log files")
+///     .sharding(Sharding::Thread)
+///     .with_sequence();
+///
+/// traccia::init_with_config(Config::default().with_target(sharded));
+/// ```
+#[derive(Clone)]
+pub struct ShardedFile {
+    path: PathBuf,
+    shards: Arc<[File]>,
+    sharding: Sharding,
+    next: Arc<AtomicUsize>,
+    sequence: Option<Arc<AtomicU64>>,
+    level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+}
+
+impl ShardedFile {
+    /// Opens `count` files next to `path`, creating the parent directories if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path the names of the files are derived from
+    /// * `count` - Number of files
+    /// * `mode` - File open mode, applied to every file
+    ///
+    /// # Returns
+    ///
+    /// A new `ShardedFile` target, or an error if `count` is zero
+    /// or one of the files couldn't be opened
+    pub fn new<P>(path: P, count: usize, mode: FileMode) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        if count == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a sharded file needs at least one shard",
+            )
+            .into());
+        }
+
+        let shards = (0..count)
+            .map(|index| File::new(shard_path(path, index), mode))
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            shards,
+            sharding: Sharding::default(),
+            next: Arc::new(AtomicUsize::new(0)),
+            sequence: None,
+            level: None,
+            only_levels: None,
+        })
+    }
+
+    /// Builder method to set how the file of each record is picked.
+    pub fn sharding(mut self, sharding: Sharding) -> Self {
+        self.sharding = sharding;
+        self
+    }
+
+    /// Builder method to prefix each line with a sequence number, shared by all the files,
+    /// e.g. `42 [INFO] Request handled`.
+    pub fn with_sequence(mut self) -> Self {
+        self.sequence = Some(Arc::new(AtomicU64::new(0)));
+        self
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`](crate::File::only_level).
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+
+    /// Returns the paths of the files, in shard order.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        (0..self.shards.len())
+            .map(|index| shard_path(&self.path, index))
+            .collect()
+    }
+
+    fn shard(&self, thread_id: ThreadId) -> &File {
+        let index = match self.sharding {
+            Sharding::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed),
+            Sharding::Thread => {
+                let mut hasher = DefaultHasher::new();
+                thread_id.hash(&mut hasher);
+                hasher.finish() as usize
+            }
+        };
+
+        &self.shards[index % self.shards.len()]
+    }
+
+    fn write_to(&self, thread_id: ThreadId, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        let shard = self.shard(thread_id);
+
+        match &self.sequence {
+            Some(sequence) => {
+                let number = sequence.fetch_add(1, Ordering::Relaxed);
+                shard.write(level, &format!("{} {}", number, formatted))
+            }
+            None => shard.write(level, formatted),
+        }
+    }
+}
+
+/// Inserts the shard number before the extension, e.g. `app.2.log` for `app.log`.
+fn shard_path(path: &Path, index: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+
+    let name = match path.extension() {
+        Some(extension) => format!("{}.{}.{}", stem, index, extension.to_string_lossy()),
+        None => format!("{}.{}", stem, index),
+    };
+
+    path.with_file_name(name)
+}
+
+impl Target for ShardedFile {
+    /// Writes the message to the next file, or to the file of the current thread.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        self.write_to(thread::current().id(), level, formatted)
+    }
+
+    /// Writes the bytes as they are, without sequence number,
+    /// to the next file or to the file of the current thread.
+    fn write_bytes(&self, level: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        self.shard(thread::current().id()).write_bytes(level, bytes)
+    }
+
+    /// Writes the record to the next file, or to the file of the thread that logged it.
+    fn write_record(&self, record: &Record, formatted: &str) -> Result<(), Error> {
+        self.write_to(record.thread_id, record.level, formatted)
+    }
+
+    /// Flushes every file, returning the first error encountered.
+    fn flush(&self) -> Result<(), Error> {
+        let mut result = Ok(());
+
+        for shard in self.shards.iter() {
+            if let Err(e) = shard.flush()
+                && result.is_ok()
+            {
+                result = Err(e);
+            }
+        }
+
+        result
+    }
+
+    /// Validates every file, returning the first error encountered.
+    fn validate(&self) -> Result<(), Error> {
+        self.shards.iter().try_for_each(File::validate)
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    /// Returns `false`, the files strip the colors.
+    fn wants_color(&self) -> bool {
+        false
+    }
+
+    /// Returns the target ID, made of the path the names of the files are derived from.
+    fn id(&self) -> TargetId {
+        TargetId::File(self.path.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_round_robin() {
+        let dir = std::env::temp_dir().join(format!("traccia-sharded-{}", std::process::id()));
+        let sharded = ShardedFile::new(dir.join("app.log"), 3, FileMode::Truncate)
+            .unwrap()
+            .with_sequence();
+
+        for i in 0..6 {
+            sharded
+                .write(LogLevel::Info, &format!("line {}", i))
+                .unwrap();
+        }
+
+        let contents = sharded
+            .paths()
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            contents,
+            [
+                "0 line 0\n3 line 3\n",
+                "1 line 1\n4 line 4\n",
+                "2 line 2\n5 line 5\n"
+            ]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_by_thread() {
+        let dir =
+            std::env::temp_dir().join(format!("traccia-sharded-thread-{}", std::process::id()));
+        let sharded = ShardedFile::new(dir.join("app"), 4, FileMode::Truncate)
+            .unwrap()
+            .sharding(Sharding::Thread);
+
+        for i in 0..5 {
+            sharded.write(LogLevel::Info, &i.to_string()).unwrap();
+        }
+
+        let contents = sharded
+            .paths()
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap())
+            .filter(|content| !content.is_empty())
+            .collect::<Vec<_>>();
+
+        assert_eq!(contents, ["0\n1\n2\n3\n4\n"]);
+        assert_eq!(sharded.paths()[3], dir.join("app.3"));
+        assert!(ShardedFile::new(dir.join("app"), 0, FileMode::Truncate).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{File, FileMode, LogLevel, Record, Target, TargetId, error::Error};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// Target that writes each record to the file mapped to its level.
+///
+/// Records with a level that has no mapping are written to the default file.
+/// Levels mapped to the same path share a single open file.
+///
+/// Created with [`File::split_by_level`].
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use std::collections::HashMap;
+/// use traccia::{Config, File, FileMode, LogLevel};
+///
+/// // Errors and fatal errors to `logs/error.log`, everything else to `logs/app.log`.
+/// let split = File::split_by_level(
+///     "logs",
+///     "app.log",
+///     HashMap::from([
+///         (LogLevel::Error, "error.log".into()),
+///         (LogLevel::Fatal, "error.log".into()),
+///     ]),
+///     FileMode::Append,
+/// )
+/// .expect("Failed to open log files");
+///
+/// let config = Config {
+///     targets: vec![Box::new(split)],
+///     ..Config::default()
+/// };
+/// ```
+#[derive(Clone)]
+pub struct LevelSplit {
+    dir: PathBuf,
+    default: File,
+    files: HashMap<LogLevel, File>,
+}
+
+impl File {
+    /// Creates a target that routes each record to a file based on its level.
+    ///
+    /// All the paths are relative to `dir`. Records with a level missing from `map`
+    /// are written to `default`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory containing the log files
+    /// * `default` - File used for the levels without a mapping
+    /// * `map` - File used for each level
+    /// * `mode` - File open mode, applied to every file
+    ///
+    /// # Returns
+    ///
+    /// A new `LevelSplit` target or an error if one of the files couldn't be opened
+    pub fn split_by_level<P, D>(
+        dir: P,
+        default: D,
+        map: HashMap<LogLevel, PathBuf>,
+        mode: FileMode,
+    ) -> Result<LevelSplit, Error>
+    where
+        P: AsRef<Path>,
+        D: AsRef<Path>,
+    {
+        let dir = dir.as_ref();
+        let mut opened: HashMap<PathBuf, File> = HashMap::new();
+
+        let mut open = |path: &Path| -> Result<File, Error> {
+            let path = dir.join(path);
+
+            if let Some(file) = opened.get(&path) {
+                return Ok(file.clone());
+            }
+
+            let file = File::new(&path, mode)?;
+            opened.insert(path, file.clone());
+
+            Ok(file)
+        };
+
+        let default = open(default.as_ref())?;
+        let files = map
+            .into_iter()
+            .map(|(level, path)| Ok((level, open(&path)?)))
+            .collect::<Result<_, Error>>()?;
+
+        Ok(LevelSplit {
+            dir: dir.to_path_buf(),
+            default,
+            files,
+        })
+    }
+}
+
+impl LevelSplit {
+    fn file_for(&self, level: LogLevel) -> &File {
+        self.files.get(&level).unwrap_or(&self.default)
+    }
+}
+
+impl Target for LevelSplit {
+    /// Writes the formatted log message to the file mapped to its level.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        self.file_for(level).write(level, formatted)
+    }
+
+    /// Writes the bytes to the file mapped to their level.
+    fn write_bytes(&self, level: LogLevel, bytes: &[u8]) -> Result<(), Error> {
+        self.file_for(level).write_bytes(level, bytes)
+    }
+
+    /// Writes the record to the file mapped to its level.
+    fn write_record(&self, record: &Record, formatted: &str) -> Result<(), Error> {
+        self.file_for(record.level).write_record(record, formatted)
+    }
+
+    /// Flushes every file, returning the first error encountered.
+    fn flush(&self) -> Result<(), Error> {
+        let mut result = self.default.flush();
+
+        for file in self.files.values() {
+            if let Err(e) = file.flush()
+                && result.is_ok()
+            {
+                result = Err(e);
+            }
+        }
+
+        result
+    }
+
+    /// Validates every file, returning the first error encountered.
+    fn validate(&self) -> Result<(), Error> {
+        self.default.validate()?;
+        self.files.values().try_for_each(File::validate)
+    }
+
+    /// Returns `false`, the files strip the colors.
+    fn wants_color(&self) -> bool {
+        false
+    }
+
+    /// Returns the target ID, made of the directory containing the files.
+    fn id(&self) -> TargetId {
+        TargetId::File(self.dir.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_split_by_level() {
+        let dir = std::env::temp_dir().join(format!("traccia-split-{}", std::process::id()));
+
+        let split = File::split_by_level(
+            &dir,
+            "app.log",
+            HashMap::from([
+                (LogLevel::Error, PathBuf::from("error.log")),
+                (LogLevel::Fatal, PathBuf::from("error.log")),
+            ]),
+            FileMode::Truncate,
+        )
+        .unwrap();
+
+        split.write(LogLevel::Info, "started").unwrap();
+        split.write(LogLevel::Error, "failed").unwrap();
+        split.write(LogLevel::Fatal, "crashed").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.join("app.log")).unwrap(),
+            "started\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("error.log")).unwrap(),
+            "failed\ncrashed\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
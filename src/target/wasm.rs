@@ -0,0 +1,146 @@
+use crate::{LogLevel, Target, TargetId, error::Error};
+
+// Provided by the page, see the docs of `WasmConsoleTarget`.
+// There is no standard import for the console: without `wasm-bindgen`,
+// the host has to provide it under a name of our choosing.
+#[link(wasm_import_module = "traccia")]
+unsafe extern "C" {
+    fn console_write(method: u32, ptr: *const u8, len: usize);
+}
+
+/// Target that writes to the browser console, when compiled to WebAssembly.
+///
+/// **The page must provide a `traccia.console_write` import, see below.**
+///
+/// Each message is written with the console method matching its level:
+/// `console.debug` for trace and debug, `console.log` for info,
+/// `console.warn` for warnings and `console.error` for errors and fatal errors.
+/// ANSI codes are stripped, as the console shows them as raw escape sequences.
+///
+/// # Required import
+///
+/// To keep the crate free of dependencies, the target doesn't use `wasm-bindgen`,
+/// and WebAssembly has no built-in way to reach the console.
+/// Instead, the module imports the `console_write` function from the `traccia` namespace.
+/// As soon as the target is used, the module can't be instantiated without it:
+/// `WebAssembly.instantiate` fails with a `LinkError`, and tools generating the bindings,
+/// such as `wasm-bindgen`, leave the import for the page to fill in.
+///
+/// The page provides it when instantiating the module:
+///
+/// ```js
+/// const methods = ["debug", "log", "warn", "error"];
+///
+/// const { instance } = await WebAssembly.instantiateStreaming(fetch("app.wasm"), {
+///     traccia: {
+///         console_write(method, ptr, len) {
+///             const bytes = new Uint8Array(instance.exports.memory.buffer, ptr, len);
+///             console[methods[method]](new TextDecoder().decode(bytes));
+///         },
+///     },
+/// });
+/// ```
+///
+/// `method` is 0 for `debug`, 1 for `log`, 2 for `warn` and 3 for `error`,
+/// and `ptr` and `len` describe the UTF-8 message in the memory of the module.
+///
+/// Threads are not available in the browser, so enable the `blocking` feature as well,
+/// the async logger needs to spawn a worker thread.
+///
+/// Only available on `wasm32`, with the `wasm` feature.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use traccia::{Config, WasmConsoleTarget};
+///
+/// let config = Config {
+///     targets: vec![Box::new(WasmConsoleTarget::new())],
+///     ..Config::default()
+/// };
+///
+/// traccia::init_with_config(config);
+/// ```
+#[derive(Clone, Default)]
+pub struct WasmConsoleTarget {
+    level: Option<LogLevel>,
+    only_levels: Option<Vec<LogLevel>>,
+}
+
+impl WasmConsoleTarget {
+    /// Creates a target writing to the browser console.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder method to only accept log messages with a level
+    /// at or above `level`.
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Builder method to only accept log messages with exactly the given level,
+    /// see [`File::only_level`](crate::File::only_level).
+    pub fn only_level(self, level: LogLevel) -> Self {
+        self.only_levels(&[level])
+    }
+
+    /// Builder method to only accept log messages with one of the given levels.
+    pub fn only_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.only_levels = Some(levels.to_vec());
+        self
+    }
+}
+
+/// Maps a level to the console method passed to `console_write`.
+fn method(level: LogLevel) -> u32 {
+    match level.severity() {
+        0 | 1 => 0, // console.debug
+        2 => 1,     // console.log
+        3 => 2,     // console.warn
+        _ => 3,     // console.error
+    }
+}
+
+impl Target for WasmConsoleTarget {
+    /// Writes the log message to the browser console.
+    fn write(&self, level: LogLevel, formatted: &str) -> Result<(), Error> {
+        // SAFETY: the pointer and length describe a valid UTF-8 buffer
+        // that outlives the call.
+        unsafe { console_write(method(level), formatted.as_ptr(), formatted.len()) };
+
+        Ok(())
+    }
+
+    fn filter_level(&self) -> Option<LogLevel> {
+        self.level
+    }
+
+    /// Returns the levels set with `only_level` or `only_levels`, if any.
+    fn only_levels(&self) -> Option<&[LogLevel]> {
+        self.only_levels.as_deref()
+    }
+
+    /// Returns `false`, the browser console doesn't render ANSI codes.
+    fn wants_color(&self) -> bool {
+        false
+    }
+
+    fn id(&self) -> TargetId {
+        TargetId::Custom("console".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method() {
+        assert_eq!(method(LogLevel::Debug), 0);
+        assert_eq!(method(LogLevel::Info), 1);
+        assert_eq!(method(LogLevel::Warn), 2);
+        assert_eq!(method(LogLevel::Fatal), 3);
+    }
+}
@@ -0,0 +1,132 @@
+//! Scoped extra targets.
+//!
+//! A thread can add targets receiving its own records, on top of the configured ones,
+//! e.g. to capture the logs of a single request and attach them to an error report.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use traccia::{MemoryTarget, with_extra_target};
+//!
+//! let capture = MemoryTarget::new();
+//!
+//! let result = with_extra_target(capture.clone(), || handle_request(request));
+//!
+//! if result.is_err() {
+//!     report.attach_logs(capture.drain());
+//! }
+//! ```
+
+use crate::{Record, Target, r#impl, suppress};
+use std::{cell::RefCell, marker::PhantomData};
+
+thread_local! {
+    /// Targets receiving the records of this thread, besides the configured ones.
+    static EXTRA_TARGETS: RefCell<Vec<Box<dyn Target>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A guard that represents an active extra target.
+///
+/// When the guard is dropped, the target is removed, along with the ones added after it.
+/// This ensures proper cleanup even in the presence of early returns or panics.
+///
+/// The guard can't be sent to another thread, as the target belongs to the thread that added it.
+pub struct ExtraTargetGuard {
+    index: usize,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ExtraTargetGuard {
+    fn drop(&mut self) {
+        EXTRA_TARGETS.with_borrow_mut(|targets| targets.truncate(self.index));
+    }
+}
+
+/// Sends the records logged on the current thread to `target` too,
+/// until the returned guard is dropped.
+///
+/// The target receives the records accepted by the global logger, by level and module,
+/// formatted with its formatter, and still applies its own filter level.
+/// Records are written on the current thread, right when they are logged.
+pub fn add_extra_target(target: impl Target + 'static) -> ExtraTargetGuard {
+    EXTRA_TARGETS.with_borrow_mut(|targets| {
+        targets.push(Box::new(target));
+
+        ExtraTargetGuard {
+            index: targets.len() - 1,
+            _not_send: PhantomData,
+        }
+    })
+}
+
+/// Runs `f` with `target` receiving the records logged on the current thread,
+/// besides the configured targets.
+///
+/// See [`add_extra_target`].
+pub fn with_extra_target<T, F, R>(target: T, f: F) -> R
+where
+    T: Target + 'static,
+    F: FnOnce() -> R,
+{
+    let _guard = add_extra_target(target);
+    f()
+}
+
+/// Writes a record to the extra targets of the current thread.
+///
+/// Logging is suppressed while writing, so that a target logging
+/// doesn't write to itself.
+pub(crate) fn write(record: &Record) {
+    EXTRA_TARGETS.with_borrow(|targets| {
+        if targets.is_empty() {
+            return;
+        }
+
+        let formatted = crate::format_record(record);
+
+        suppress::suppress(|| {
+            for target in targets.iter().filter(|target| target.accepts(record.level)) {
+                let message = r#impl::colored_for(target.as_ref(), &formatted);
+
+                if let Err(e) = target.write_record(record, &message) {
+                    r#impl::report_write_error(target.as_ref(), e);
+                }
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LogLevel, MemoryTarget};
+
+    #[test]
+    fn test_extra_targets_scoped() {
+        let outer = MemoryTarget::new();
+        let inner = MemoryTarget::new().min_level(LogLevel::Warn);
+        let record = |level| Record::builder(level, "captured").build();
+
+        with_extra_target(outer.clone(), || {
+            write(&record(LogLevel::Info));
+
+            let guard = add_extra_target(inner.clone());
+
+            write(&record(LogLevel::Info));
+            write(&record(LogLevel::Error));
+
+            std::thread::spawn(move || write(&record(LogLevel::Error)))
+                .join()
+                .unwrap();
+
+            drop(guard);
+            write(&record(LogLevel::Error));
+        });
+
+        write(&record(LogLevel::Error));
+
+        assert_eq!(outer.len(), 4);
+        assert_eq!(inner.len(), 1);
+        assert!(EXTRA_TARGETS.with_borrow(Vec::is_empty));
+    }
+}
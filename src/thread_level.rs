@@ -0,0 +1,95 @@
+//! Per-thread level overrides.
+//!
+//! A thread can replace the global level for its own log calls, e.g. to mute
+//! a chatty background task while the rest of the application keeps logging at `Debug`.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use traccia::{LogLevel, with_thread_level};
+//!
+//! std::thread::spawn(|| {
+//!     with_thread_level(LogLevel::Warn, || {
+//!         // Only warnings and errors are logged here
+//!         process_batch();
+//!     });
+//! });
+//! ```
+
+use crate::LogLevel;
+use std::{cell::Cell, marker::PhantomData};
+
+thread_local! {
+    /// Level overriding the global one on this thread.
+    static THREAD_LEVEL: Cell<Option<LogLevel>> = const { Cell::new(None) };
+}
+
+/// A guard that represents an active level override.
+///
+/// When the guard is dropped, the previous level of the thread is restored.
+/// This ensures proper cleanup even in the presence of early returns or panics.
+///
+/// The guard can't be sent to another thread, as the override belongs to the thread that set it.
+#[must_use = "the level override is removed as soon as the guard is dropped"]
+pub struct ThreadLevelGuard {
+    previous: Option<LogLevel>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ThreadLevelGuard {
+    fn drop(&mut self) {
+        THREAD_LEVEL.set(self.previous);
+    }
+}
+
+/// Replaces the global level with `level` for the log calls of the current thread,
+/// until the returned guard is dropped.
+///
+/// Overrides can be nested, the innermost one wins.
+pub fn set_thread_level(level: LogLevel) -> ThreadLevelGuard {
+    ThreadLevelGuard {
+        previous: THREAD_LEVEL.replace(Some(level)),
+        _not_send: PhantomData,
+    }
+}
+
+/// Runs `f` with `level` replacing the global level on the current thread.
+pub fn with_thread_level<F, R>(level: LogLevel, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let _guard = set_thread_level(level);
+    f()
+}
+
+/// Returns the level override of the current thread, if any.
+///
+/// Custom [`Logger`](crate::Logger) implementations should prefer it
+/// over their configured level in [`Logger::enabled`](crate::Logger::enabled).
+pub fn thread_level() -> Option<LogLevel> {
+    THREAD_LEVEL.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_restored() {
+        assert_eq!(thread_level(), None);
+
+        with_thread_level(LogLevel::Warn, || {
+            let guard = set_thread_level(LogLevel::Trace);
+            assert_eq!(thread_level(), Some(LogLevel::Trace));
+
+            drop(guard);
+            assert_eq!(thread_level(), Some(LogLevel::Warn));
+        });
+
+        let _ = std::panic::catch_unwind(|| {
+            with_thread_level(LogLevel::Error, || panic!("boom"));
+        });
+
+        assert_eq!(thread_level(), None);
+    }
+}
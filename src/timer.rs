@@ -0,0 +1,145 @@
+//! Scoped timers that log how long a block of code took.
+//!
+//! A [`Timer`] records the instant it was created and, when dropped,
+//! logs `"<label> took <duration>"` at the chosen level.
+//! The span context active at creation is attached to the record,
+//! so the timing line carries the same context as the code it measures.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use traccia::{LogLevel, span, timed};
+//!
+//! fn fetch_user(id: u64) {
+//!     let _span = span!("request", "user_id" => id);
+//!     let _t = timed!(LogLevel::Debug, "db query");
+//!
+//!     // ... run the query ...
+//! }
+//! // Logs: [DEBUG] db query took 1.42ms [request: user_id=42]
+//! ```
+
+use crate::{LogLevel, Record, Span};
+use std::{
+    panic::Location,
+    time::{Duration, Instant},
+};
+
+/// Formats a duration in a compact, human readable way.
+///
+/// The unit is picked based on the magnitude of the duration
+/// (`ns`, `µs`, `ms`, `s`), and at most two decimals are shown.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use traccia::human_duration;
+///
+/// assert_eq!(human_duration(Duration::from_nanos(850)), "850ns");
+/// assert_eq!(human_duration(Duration::from_micros(1500)), "1.50ms");
+/// assert_eq!(human_duration(Duration::from_secs(2)), "2.00s");
+/// ```
+pub fn human_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1_000.0)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1_000_000.0)
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+/// A guard that logs the time elapsed since its creation when dropped.
+///
+/// The message is logged even if the scope is left because of a panic,
+/// in which case ` (panicked)` is appended to it.
+///
+/// Usually created through the [`timed!`](crate::timed) macro,
+/// which also fills in the module path of the call site.
+pub struct Timer {
+    level: LogLevel,
+    label: String,
+    start: Instant,
+    context: Vec<Span>,
+    trace_id: Option<String>,
+    module_path: Option<&'static str>,
+    location: &'static Location<'static>,
+}
+
+impl Timer {
+    /// Starts a new timer with the given level and label.
+    ///
+    /// The span context and the trace id active at this point are captured and
+    /// attached to the record logged on drop.
+    #[track_caller]
+    pub fn new(level: LogLevel, label: impl Into<String>) -> Self {
+        Self {
+            level,
+            label: label.into(),
+            start: Instant::now(),
+            context: Record::collect_context(Vec::new()),
+            trace_id: crate::trace_id(),
+            module_path: None,
+            location: Location::caller(),
+        }
+    }
+
+    /// Sets the module path reported in the record.
+    ///
+    /// Used by the `timed!` macro, since `#[track_caller]`
+    /// can only provide the file and line of the call site.
+    #[doc(hidden)]
+    pub fn module_path(mut self, module_path: &'static str) -> Self {
+        self.module_path = Some(module_path);
+        self
+    }
+
+    /// Returns the time elapsed since the timer was started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let mut message = format!("{} took {}", self.label, human_duration(self.elapsed()));
+
+        if std::thread::panicking() {
+            message.push_str(" (panicked)");
+        }
+
+        let mut record = Record::builder(self.level, message)
+            .target(self.module_path.unwrap_or_default())
+            .file(self.location.file())
+            .line(self.location.line())
+            .context(std::mem::take(&mut self.context));
+
+        if let Some(module_path) = self.module_path {
+            record = record.module_path(module_path);
+        }
+
+        let mut record = record.build();
+        record.trace_id = self.trace_id.take();
+
+        crate::__log(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_duration_units() {
+        assert_eq!(human_duration(Duration::ZERO), "0ns");
+        assert_eq!(human_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(human_duration(Duration::from_nanos(1_250)), "1.25µs");
+        assert_eq!(human_duration(Duration::from_micros(12_340)), "12.34ms");
+        assert_eq!(human_duration(Duration::from_millis(61_500)), "61.50s");
+    }
+}
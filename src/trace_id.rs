@@ -0,0 +1,104 @@
+//! Per-thread correlation id attached to every record.
+//!
+//! A service sets the id once per request, e.g. from an incoming header, and every
+//! record logged on the thread carries it in [`Record::trace_id`](crate::Record::trace_id),
+//! without adding it to each span. Structured formatters emit it as a dedicated field,
+//! `trace_id` in JSON.
+//!
+//! The id is not inherited by other threads: read it with [`trace_id`] before
+//! spawning, and set it again on the new thread.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use traccia::{info, set_trace_id};
+//!
+//! fn handle(request: &Request) {
+//!     let _trace = set_trace_id(request.header("x-request-id"));
+//!
+//!     info!("Handling request");
+//!     // {"level":"INFO","message":"Handling request",...,"trace_id":"4bf92f35"}
+//! }
+//! ```
+
+use std::{cell::RefCell, marker::PhantomData};
+
+thread_local! {
+    /// Correlation id of the records logged on this thread.
+    static TRACE_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// A guard that represents an active trace id.
+///
+/// When the guard is dropped, the previous trace id of the thread is restored.
+/// This ensures proper cleanup even in the presence of early returns or panics.
+///
+/// The guard can't be sent to another thread, as the id belongs to the thread that set it.
+#[must_use = "the trace id is removed as soon as the guard is dropped"]
+pub struct TraceIdGuard {
+    previous: Option<String>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for TraceIdGuard {
+    fn drop(&mut self) {
+        TRACE_ID.set(self.previous.take());
+    }
+}
+
+/// Attaches `id` to the records logged on the current thread,
+/// until the returned guard is dropped.
+///
+/// Ids can be nested, the innermost one wins.
+pub fn set_trace_id(id: impl Into<String>) -> TraceIdGuard {
+    TraceIdGuard {
+        previous: TRACE_ID.replace(Some(id.into())),
+        _not_send: PhantomData,
+    }
+}
+
+/// Runs `f` with `id` attached to the records logged on the current thread.
+pub fn with_trace_id<F, R>(id: impl Into<String>, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let _guard = set_trace_id(id);
+    f()
+}
+
+/// Removes the trace id of the current thread, e.g. when a worker thread
+/// is done with a request. Active guards still restore their previous id when dropped.
+pub fn clear_trace_id() {
+    TRACE_ID.set(None);
+}
+
+/// Returns the trace id of the current thread, if any.
+pub fn trace_id() -> Option<String> {
+    TRACE_ID.with_borrow(Clone::clone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_id_restored() {
+        assert_eq!(trace_id(), None);
+
+        with_trace_id("outer", || {
+            let guard = set_trace_id("inner");
+            assert_eq!(trace_id().as_deref(), Some("inner"));
+
+            drop(guard);
+            assert_eq!(trace_id().as_deref(), Some("outer"));
+
+            clear_trace_id();
+            assert_eq!(trace_id(), None);
+        });
+
+        let _guard = set_trace_id("request");
+        std::thread::spawn(|| assert_eq!(trace_id(), None))
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,91 @@
+//! Messages already logged by [`log_unique!`](crate::log_unique).
+//!
+//! Each distinct message is remembered by a hash of its level and text,
+//! so memory doesn't depend on the message length. At most [`CAPACITY`] messages
+//! are remembered: past that, the oldest ones are forgotten and may be logged again.
+
+use crate::LogLevel;
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{LazyLock, Mutex},
+};
+
+/// Maximum number of distinct messages remembered.
+const CAPACITY: usize = 4096;
+
+static SEEN: LazyLock<Mutex<Seen>> = LazyLock::new(|| Mutex::new(Seen::new(CAPACITY)));
+
+/// Hashes of the messages seen so far, with their insertion order for eviction.
+struct Seen {
+    capacity: usize,
+    hashes: HashSet<u64>,
+    order: VecDeque<u64>,
+}
+
+impl Seen {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            hashes: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Remembers the hash, returning `true` if it wasn't already there.
+    fn insert(&mut self, hash: u64) -> bool {
+        if !self.hashes.insert(hash) {
+            return false;
+        }
+
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.hashes.remove(&oldest);
+        }
+
+        self.order.push_back(hash);
+        true
+    }
+}
+
+/// Returns `true` the first time a message is seen at a level.
+///
+/// This is used by the [`log_unique!`](crate::log_unique) macro and is not meant to be called directly.
+#[doc(hidden)]
+pub fn __first_time(level: LogLevel, message: &str) -> bool {
+    let mut hasher = DefaultHasher::new();
+
+    level.hash(&mut hasher);
+    message.hash(&mut hasher);
+
+    SEEN.lock()
+        .map(|mut seen| seen.insert(hasher.finish()))
+        .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_time() {
+        assert!(__first_time(LogLevel::Warn, "unique: x=1"));
+        assert!(!__first_time(LogLevel::Warn, "unique: x=1"));
+        assert!(__first_time(LogLevel::Warn, "unique: x=2"));
+        assert!(__first_time(LogLevel::Error, "unique: x=1"));
+    }
+
+    #[test]
+    fn test_oldest_evicted() {
+        let mut seen = Seen::new(2);
+
+        assert!(seen.insert(1));
+        assert!(seen.insert(2));
+        assert!(!seen.insert(1));
+        assert!(seen.insert(3));
+        assert!(seen.insert(1));
+        assert!(!seen.insert(3));
+        assert_eq!(seen.order.len(), 2);
+    }
+}
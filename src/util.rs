@@ -1,7 +1,29 @@
+use crate::{LogLevel, target::ControlChars};
+use std::{
+    borrow::Cow,
+    sync::OnceLock,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Instant the logger was initialized at, or of the first call if earlier.
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Returns the instant elapsed timestamps are relative to.
+pub(crate) fn start_time() -> Instant {
+    *START.get_or_init(Instant::now)
+}
+
 /// Removes ANSI escape codes from a string.
+#[cfg(test)]
 pub(crate) fn strip_ansi_codes(s: &str) -> String {
-    if s.is_empty() || !s.contains('\x1b') {
-        return s.to_string();
+    strip_ansi_codes_cow(s).into_owned()
+}
+
+/// Removes ANSI escape codes from a string,
+/// borrowing it when there is nothing to remove.
+pub(crate) fn strip_ansi_codes_cow(s: &str) -> Cow<'_, str> {
+    if !s.contains('\x1b') {
+        return Cow::Borrowed(s);
     }
 
     let mut buf = String::with_capacity(s.len());
@@ -24,5 +46,208 @@ pub(crate) fn strip_ansi_codes(s: &str) -> String {
         buf.shrink_to_fit();
     }
 
+    Cow::Owned(buf)
+}
+
+/// Applies `f` to a string that may already be owned, borrowing only when both do.
+pub(crate) fn map_cow<'a, F>(s: Cow<'a, str>, f: F) -> Cow<'a, str>
+where
+    F: for<'b> FnOnce(&'b str) -> Cow<'b, str>,
+{
+    match s {
+        Cow::Borrowed(s) => f(s),
+        Cow::Owned(mut s) => {
+            let range = match f(&s) {
+                Cow::Owned(mapped) => return Cow::Owned(mapped),
+                Cow::Borrowed(mapped) => subslice_range(&s, mapped).ok_or(mapped),
+            };
+
+            // Keep the part of `s` that `f` borrowed in place instead of copying it
+            match range {
+                Ok((start, end)) => {
+                    s.truncate(end);
+                    s.drain(..start);
+                    Cow::Owned(s)
+                }
+                Err(mapped) => Cow::Owned(mapped.to_string()),
+            }
+        }
+    }
+}
+
+/// Returns the byte range of `sub` in `s`, if `sub` is a slice of `s`.
+fn subslice_range(s: &str, sub: &str) -> Option<(usize, usize)> {
+    let start = (sub.as_ptr() as usize).checked_sub(s.as_ptr() as usize)?;
+    let end = start + sub.len();
+
+    (end <= s.len()).then_some((start, end))
+}
+
+/// Removes a single trailing line break, since the targets add their own.
+///
+/// If `normalize` is set, `\r\n` and lone `\r` line breaks are turned into `\n` first.
+pub(crate) fn trim_newline(s: &str, normalize: bool) -> Cow<'_, str> {
+    if normalize && s.contains('\r') {
+        let mut normalized = s.replace("\r\n", "\n").replace('\r', "\n");
+
+        if normalized.ends_with('\n') {
+            normalized.pop();
+        }
+
+        return Cow::Owned(normalized);
+    }
+
+    let trimmed = s
+        .strip_suffix('\n')
+        .map_or(s, |s| s.strip_suffix('\r').unwrap_or(s));
+
+    Cow::Borrowed(trimmed)
+}
+
+/// Escapes or removes the control characters of a string, except tabs.
+pub(crate) fn sanitize_control_chars(s: &str, mode: ControlChars) -> Cow<'_, str> {
+    let is_control = |c: char| c.is_control() && c != '\t';
+
+    if mode == ControlChars::Keep || !s.contains(is_control) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut buf = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        if !is_control(c) {
+            buf.push(c);
+            continue;
+        }
+
+        if mode == ControlChars::Escape {
+            match c {
+                '\n' => buf.push_str("\\n"),
+                '\r' => buf.push_str("\\r"),
+                c => buf.push_str(&format!("\\x{:02x}", c as u32)),
+            }
+        }
+    }
+
+    Cow::Owned(buf)
+}
+
+/// Maps a level to a syslog severity, as used by the journal and GELF.
+pub(crate) fn syslog_severity(level: LogLevel) -> u8 {
+    match level.severity() {
+        0 | 1 => 7, // debug
+        2 => 6,     // info
+        3 => 4,     // warning
+        4 => 3,     // err
+        _ => 2,     // crit
+    }
+}
+
+/// Renders a string as a quoted JSON string, escaping it as needed.
+pub(crate) fn json_string(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len() + 2);
+
+    buf.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if c.is_control() => buf.push_str(&format!("\\u{:04x}", c as u32)),
+            c => buf.push(c),
+        }
+    }
+
+    buf.push('"');
     buf
 }
+
+/// Returns the `(year, month, day)` date of a number of days since the epoch
+/// (Howard Hinnant's algorithm).
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Returns the number of days since the epoch of a date, the inverse of [`civil_from_days`].
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+/// Formats a point in time as an RFC 3339 UTC timestamp with milliseconds,
+/// e.g. `2025-04-04T12:30:05.123Z`.
+pub(crate) fn rfc3339_utc(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+
+    let secs_of_day = secs % 86_400;
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc3339_timestamp() {
+        let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(1_709_210_096_789);
+
+        assert_eq!(rfc3339_utc(time), "2024-02-29T12:34:56.789Z");
+        assert_eq!(
+            rfc3339_utc(SystemTime::UNIX_EPOCH),
+            "1970-01-01T00:00:00.000Z"
+        );
+    }
+
+    #[test]
+    fn test_map_cow_keeps_owned_string() {
+        let owned = Cow::Owned::<str>("  padded\n".to_string());
+        let pointer = owned.as_ptr();
+
+        let mapped = map_cow(owned, |s| Cow::Borrowed(s.trim_end()));
+        assert_eq!(mapped, "  padded");
+        assert_eq!(mapped.as_ptr(), pointer);
+
+        let mapped = map_cow(Cow::Owned("a b".to_string()), |s| {
+            Cow::Borrowed(s.split(' ').nth(1).unwrap())
+        });
+        assert_eq!(mapped, "b");
+
+        let mapped = map_cow(Cow::Owned("a".to_string()), |_| Cow::Borrowed("static"));
+        assert_eq!(mapped, "static");
+
+        assert!(matches!(
+            map_cow(Cow::Borrowed("a"), |s| Cow::Borrowed(s)),
+            Cow::Borrowed("a")
+        ));
+    }
+}